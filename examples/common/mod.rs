@@ -0,0 +1,208 @@
+// Copyright Claudio Mattera 2024-2026.
+//
+// Distributed under the MIT License or the Apache 2.0 License at your option.
+// See the accompanying files License-MIT.txt and License-Apache-2.0.txt, or
+// online at
+// https://opensource.org/licenses/MIT
+// https://opensource.org/licenses/Apache-2.0
+
+//! Transport selection for examples
+//!
+//! Examples run on a transport chosen at runtime through the environment
+//! variable `SCD4X_TRANSPORT`:
+//!
+//! *   `ftdi` (default): a sensor attached to a FT232H board.
+//! *   `i2c-dev` or `i2c-dev:PATH`: a sensor attached to a Linux I²C bus,
+//!     `/dev/i2c-1` by default.
+//! *   `sim`: an in-process simulated sensor running in virtual time.
+//!
+//! The simulator allows running examples without hardware, e.g. in CI.
+
+use core::error::Error;
+use core::str::FromStr;
+use core::time::Duration;
+use std::env::var;
+use std::env::VarError;
+use std::path::PathBuf;
+use std::thread::sleep;
+
+use log::info;
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal::i2c::Error as _;
+use embedded_hal::i2c::ErrorKind;
+use embedded_hal::i2c::ErrorType;
+use embedded_hal::i2c::I2c;
+use embedded_hal::i2c::Operation;
+use embedded_hal::i2c::SevenBitAddress;
+use ftdi_embedded_hal::FtHal;
+use linux_embedded_hal::I2cdev;
+
+use uom::si::f32::Ratio;
+use uom::si::f32::ThermodynamicTemperature;
+use uom::si::ratio::part_per_million;
+use uom::si::ratio::percent;
+use uom::si::thermodynamic_temperature::degree_celsius;
+
+use scd4x_rs::sim::Simulator;
+use scd4x_rs::sim::VirtualClock;
+use scd4x_rs::Sample;
+
+/// Environment variable selecting the transport
+const TRANSPORT_VARIABLE: &str = "SCD4X_TRANSPORT";
+
+/// Default Linux I²C bus
+const DEFAULT_I2C_DEV: &str = "/dev/i2c-1";
+
+/// Frequency of the FTDI I²C bus in Hz
+const FTDI_FREQUENCY: u32 = 400_000;
+
+/// A transport to a sensor
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Transport {
+    /// A sensor attached to a FT232H board
+    Ftdi,
+
+    /// A sensor attached to a Linux I²C bus
+    I2cDev(PathBuf),
+
+    /// An in-process simulated sensor running in virtual time
+    Simulator,
+}
+
+impl Transport {
+    /// Select the transport from the environment
+    ///
+    /// # Errors
+    ///
+    /// Return an error if the environment variable is not a valid transport.
+    pub fn from_env() -> Result<Self, Box<dyn Error>> {
+        match var(TRANSPORT_VARIABLE) {
+            Ok(value) => Ok(value.parse()?),
+            Err(VarError::NotPresent) => Ok(Self::Ftdi),
+            Err(error) => Err(error.into()),
+        }
+    }
+}
+
+impl FromStr for Transport {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.split_once(':') {
+            None if value == "ftdi" => Ok(Self::Ftdi),
+            None if value == "i2c-dev" => Ok(Self::I2cDev(PathBuf::from(DEFAULT_I2C_DEV))),
+            None if value == "sim" => Ok(Self::Simulator),
+            Some(("i2c-dev", path)) => Ok(Self::I2cDev(PathBuf::from(path))),
+            _ => Err(format!("Unknown transport {value}")),
+        }
+    }
+}
+
+/// An I²C bus on any transport
+pub enum Bus<'clock> {
+    /// FTDI I²C bus
+    Ftdi(ftdi_embedded_hal::I2c<ftdi::Device>),
+
+    /// Linux I²C bus
+    I2cDev(I2cdev),
+
+    /// Simulated sensor
+    Simulator(Simulator<&'clock VirtualClock, fn(Duration) -> Sample>),
+}
+
+impl ErrorType for Bus<'_> {
+    type Error = ErrorKind;
+}
+
+impl I2c for Bus<'_> {
+    fn transaction(
+        &mut self,
+        address: SevenBitAddress,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        match *self {
+            Self::Ftdi(ref mut i2c) => i2c
+                .transaction(address, operations)
+                .map_err(|error| error.kind()),
+            Self::I2cDev(ref mut i2c) => i2c
+                .transaction(address, operations)
+                .map_err(|error| error.kind()),
+            Self::Simulator(ref mut i2c) => i2c.transaction(address, operations),
+        }
+    }
+}
+
+/// A delay on any transport
+#[derive(Copy, Clone)]
+pub enum Delay<'clock> {
+    /// Delay in real time
+    Real,
+
+    /// Delay in virtual time
+    Virtual(&'clock VirtualClock),
+}
+
+impl DelayNs for Delay<'_> {
+    fn delay_ns(&mut self, ns: u32) {
+        match *self {
+            Self::Real => sleep(Duration::from_nanos(u64::from(ns))),
+            Self::Virtual(mut clock) => clock.delay_ns(ns),
+        }
+    }
+
+    fn delay_ms(&mut self, ms: u32) {
+        match *self {
+            Self::Real => sleep(Duration::from_millis(u64::from(ms))),
+            Self::Virtual(mut clock) => clock.delay_ms(ms),
+        }
+    }
+}
+
+/// Open an I²C bus on a transport
+///
+/// The clock is only used by the simulator.
+///
+/// # Errors
+///
+/// Return an error if the bus cannot be opened.
+pub fn open<'clock>(
+    transport: &Transport,
+    clock: &'clock VirtualClock,
+) -> Result<(Bus<'clock>, Delay<'clock>), Box<dyn Error>> {
+    match *transport {
+        Transport::Ftdi => {
+            info!("Create FTDI device");
+            let device = ftdi::find_by_vid_pid(0x0403, 0x6014)
+                .interface(ftdi::Interface::A)
+                .open()?;
+
+            info!("Initialize I²C bus");
+            let hal = FtHal::init_freq(device, FTDI_FREQUENCY)?;
+            let i2c = hal.i2c()?;
+
+            Ok((Bus::Ftdi(i2c), Delay::Real))
+        }
+        Transport::I2cDev(ref path) => {
+            info!("Open I²C bus {}", path.display());
+            let i2c = I2cdev::new(path)?;
+
+            Ok((Bus::I2cDev(i2c), Delay::Real))
+        }
+        Transport::Simulator => {
+            info!("Create simulated sensor");
+            let simulator = Simulator::new(clock, indoor_air as fn(Duration) -> Sample);
+
+            Ok((Bus::Simulator(simulator), Delay::Virtual(clock)))
+        }
+    }
+}
+
+/// Indoor air with CO₂ concentration rising by 6 ppm per minute from 600 ppm
+fn indoor_air(time: Duration) -> Sample {
+    Sample {
+        co2: Ratio::new::<part_per_million>(600.0 + time.as_secs_f32() / 10.0),
+        temperature: ThermodynamicTemperature::new::<degree_celsius>(21.0),
+        humidity: Ratio::new::<percent>(40.0),
+    }
+}
@@ -6,7 +6,11 @@
 // https://opensource.org/licenses/MIT
 // https://opensource.org/licenses/Apache-2.0
 
-//! Example of continuous operation of SCD4x sensor through a FT232H board
+//! Example of continuous operation of SCD4x sensor
+//!
+//! The transport is selected at runtime, see module [`common`].
+
+mod common;
 
 use core::error::Error;
 use core::time::Duration;
@@ -17,16 +21,18 @@
 
 use embedded_hal::delay::DelayNs;
 use embedded_hal::i2c::I2c;
-use ftdi_embedded_hal::Delay;
-use ftdi_embedded_hal::FtHal;
 
 use uom::si::ratio::part_per_million;
 use uom::si::ratio::percent;
 use uom::si::thermodynamic_temperature::degree_celsius;
 
+use scd4x_rs::sim::VirtualClock;
 use scd4x_rs::Error as Scd4xError;
 use scd4x_rs::Scd4x;
 
+use self::common::open;
+use self::common::Transport;
+
 /// Number of measurements to read
 const MEASUREMENTS_COUNT: usize = 10;
 
@@ -37,27 +43,23 @@
 fn main() -> Result<(), Box<dyn Error>> {
     init_logger();
 
-    info!("Create FTDI device");
-    let device = ftdi::find_by_vid_pid(0x0403, 0x6014)
-        .interface(ftdi::Interface::A)
-        .open()?;
+    let transport = Transport::from_env()?;
+    info!("Use transport {transport:?}");
 
-    info!("Initialize I²C bus");
-    let hal = FtHal::init_freq(device, 400_000)?;
-    let i2c = hal.i2c()?;
+    let clock = VirtualClock::new();
+    let (i2c, delay) = open(&transport, &clock)?;
 
-    let _i2c = handle_scd4x(i2c)?;
+    let _i2c = handle_scd4x(i2c, delay)?;
 
     Ok(())
 }
 
 /// Execute operations on sensor SCD4x
-fn handle_scd4x<I2C>(i2c: I2C) -> Result<I2C, Box<dyn Error>>
+fn handle_scd4x<I2C, D>(i2c: I2C, mut delay: D) -> Result<I2C, Box<dyn Error>>
 where
     I2C: I2c,
+    D: DelayNs + Copy,
 {
-    let mut delay = Delay::new();
-
     let sensor = Scd4x::new(i2c, delay);
 
     let mut sensor = sensor.stop_periodic_measurement()?;
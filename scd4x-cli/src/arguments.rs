@@ -0,0 +1,500 @@
+// Copyright Claudio Mattera 2024-2026.
+//
+// Distributed under the MIT License or the Apache 2.0 License at your option.
+// See the accompanying files LICENSE-MIT.txt and LICENSE-APACHE-2.0.txt, or
+// online at
+// https://opensource.org/licenses/MIT
+// https://opensource.org/licenses/Apache-2.0
+
+//! Parsing of command-line arguments
+
+use core::fmt;
+use std::path::PathBuf;
+
+use scd4x_rs::DEFAULT_ADDRESS;
+
+/// Default I²C bus
+const DEFAULT_BUS: &str = "/dev/i2c-1";
+
+/// Names of all commands
+const COMMANDS: [&str; 8] = [
+    "serial",
+    "read",
+    "self-test",
+    "frc",
+    "get",
+    "set",
+    "persist",
+    "factory-reset",
+];
+
+/// Usage message
+pub(crate) const USAGE: &str = "\
+Usage: scd4x [--bus PATH] [--address ADDRESS] COMMAND
+
+Options:
+    -b, --bus PATH          I²C bus [default: /dev/i2c-1]
+    -a, --address ADDRESS   I²C address, decimal or hexadecimal [default: 0x62]
+    -h, --help              Print this message
+
+Commands:
+    serial                  Print the serial number
+    read                    Print a single-shot measurement (not on SCD40)
+    read --continuous [--count N]
+                            Print periodic measurements, forever or N times
+    self-test               Perform a self-test
+    frc PPM                 Perform a forced recalibration to a reference
+                            CO₂ concentration
+    get SETTING             Print a setting
+    set SETTING VALUE       Change a setting until power-off
+    persist                 Persist settings to EEPROM
+    factory-reset           Restore factory settings
+
+Settings:
+    temperature-offset          Temperature offset in °C
+    sensor-altitude             Sensor altitude in m
+    ambient-pressure            Ambient pressure in hPa
+    automatic-self-calibration  Automatic self-calibration, on or off
+";
+
+/// Command-line arguments
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Arguments {
+    /// Path of the I²C bus
+    pub(crate) bus: PathBuf,
+
+    /// I²C address of the sensor
+    pub(crate) address: u8,
+
+    /// Command to execute
+    pub(crate) command: Command,
+}
+
+/// A command to execute
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) enum Command {
+    /// Print the usage message
+    Help,
+
+    /// Print the serial number
+    Serial,
+
+    /// Print measurements
+    Read {
+        /// Whether to use periodic instead of single-shot measurements
+        continuous: bool,
+
+        /// Number of periodic measurements, or `None` for reading forever
+        count: Option<u32>,
+    },
+
+    /// Perform a self-test
+    SelfTest,
+
+    /// Perform a forced recalibration to a reference CO₂ concentration in ppm
+    Frc(f32),
+
+    /// Print a setting
+    Get(Setting),
+
+    /// Change a setting
+    Set(Value),
+
+    /// Persist settings to EEPROM
+    Persist,
+
+    /// Restore factory settings
+    FactoryReset,
+}
+
+/// A sensor setting
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Setting {
+    /// Temperature offset
+    TemperatureOffset,
+
+    /// Sensor altitude
+    SensorAltitude,
+
+    /// Ambient pressure
+    AmbientPressure,
+
+    /// Automatic self-calibration
+    AutomaticSelfCalibration,
+}
+
+/// A value for a sensor setting
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) enum Value {
+    /// Temperature offset in °C
+    TemperatureOffset(f32),
+
+    /// Sensor altitude in m
+    SensorAltitude(f32),
+
+    /// Ambient pressure in hPa
+    AmbientPressure(f32),
+
+    /// Whether automatic self-calibration is enabled
+    AutomaticSelfCalibration(bool),
+}
+
+/// An error in command-line arguments
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum ArgumentError {
+    /// No command was given
+    MissingCommand,
+
+    /// The command is unknown
+    UnknownCommand(String),
+
+    /// The command was given wrong arguments
+    WrongArguments(String),
+
+    /// The option is unknown
+    UnknownOption(String),
+
+    /// The option requires a value
+    MissingValue(&'static str),
+
+    /// The value is invalid
+    InvalidValue(String),
+
+    /// The setting is unknown
+    UnknownSetting(String),
+
+    /// The option is only valid for continuous reads
+    NotContinuous(&'static str),
+}
+
+impl core::error::Error for ArgumentError {}
+
+impl fmt::Display for ArgumentError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Self::MissingCommand => write!(formatter, "Missing command"),
+            Self::UnknownCommand(ref command) => write!(formatter, "Unknown command {command}"),
+            Self::WrongArguments(ref command) => {
+                write!(formatter, "Wrong arguments for command {command}")
+            }
+            Self::UnknownOption(ref option) => write!(formatter, "Unknown option {option}"),
+            Self::MissingValue(option) => write!(formatter, "Missing value for option {option}"),
+            Self::InvalidValue(ref value) => write!(formatter, "Invalid value {value}"),
+            Self::UnknownSetting(ref setting) => write!(formatter, "Unknown setting {setting}"),
+            Self::NotContinuous(option) => {
+                write!(formatter, "Option {option} requires read --continuous")
+            }
+        }
+    }
+}
+
+/// Parse command-line arguments, excluding the program name
+///
+/// Options can appear anywhere, either before or after the command.
+///
+/// # Errors
+///
+/// Return an error if the arguments are not valid.
+pub(crate) fn parse<I>(arguments: I) -> Result<Arguments, ArgumentError>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut bus = PathBuf::from(DEFAULT_BUS);
+    let mut address = DEFAULT_ADDRESS;
+    let mut continuous = false;
+    let mut count = None;
+    let mut help = false;
+    let mut positionals = Vec::new();
+
+    let mut arguments = arguments.into_iter();
+    while let Some(argument) = arguments.next() {
+        match argument.as_str() {
+            "-b" | "--bus" => bus = PathBuf::from(next_value(&mut arguments, "--bus")?),
+            "-a" | "--address" => {
+                address = parse_address(&next_value(&mut arguments, "--address")?)?;
+            }
+            "-c" | "--continuous" => continuous = true,
+            "-n" | "--count" => {
+                count = Some(parse_number(&next_value(&mut arguments, "--count")?)?);
+            }
+            "-h" | "--help" => help = true,
+            option if option.starts_with('-') && option.parse::<f32>().is_err() => {
+                return Err(ArgumentError::UnknownOption(argument));
+            }
+            _ => positionals.push(argument),
+        }
+    }
+
+    let command = if help {
+        Command::Help
+    } else {
+        parse_command(&positionals, continuous, count)?
+    };
+
+    Ok(Arguments {
+        bus,
+        address,
+        command,
+    })
+}
+
+/// Parse a command and its arguments
+///
+/// # Errors
+///
+/// Return an error if the command or its arguments are not valid.
+fn parse_command(
+    positionals: &[String],
+    continuous: bool,
+    count: Option<u32>,
+) -> Result<Command, ArgumentError> {
+    let positionals: Vec<&str> = positionals.iter().map(String::as_str).collect();
+
+    let command = match *positionals.as_slice() {
+        [] => return Err(ArgumentError::MissingCommand),
+        ["serial"] => Command::Serial,
+        ["read"] => Command::Read { continuous, count },
+        ["self-test"] => Command::SelfTest,
+        ["frc", ppm] => Command::Frc(parse_number(ppm)?),
+        ["get", setting] => Command::Get(parse_setting(setting)?),
+        ["set", setting, value] => Command::Set(parse_value(parse_setting(setting)?, value)?),
+        ["persist"] => Command::Persist,
+        ["factory-reset"] => Command::FactoryReset,
+        [command, ..] if COMMANDS.contains(&command) => {
+            return Err(ArgumentError::WrongArguments(command.to_owned()));
+        }
+        [command, ..] => return Err(ArgumentError::UnknownCommand(command.to_owned())),
+    };
+
+    if count.is_some() && !continuous {
+        return Err(ArgumentError::NotContinuous("--count"));
+    }
+    if continuous && !matches!(command, Command::Read { .. }) {
+        return Err(ArgumentError::NotContinuous("--continuous"));
+    }
+
+    Ok(command)
+}
+
+/// Return the value of an option
+///
+/// # Errors
+///
+/// Return an error if there are no more arguments.
+fn next_value(
+    arguments: &mut impl Iterator<Item = String>,
+    option: &'static str,
+) -> Result<String, ArgumentError> {
+    arguments.next().ok_or(ArgumentError::MissingValue(option))
+}
+
+/// Parse a 7-bit I²C address, either decimal or hexadecimal with prefix `0x`
+///
+/// # Errors
+///
+/// Return an error if the address is not valid.
+fn parse_address(value: &str) -> Result<u8, ArgumentError> {
+    let address = match value.strip_prefix("0x") {
+        Some(hexadecimal) => u8::from_str_radix(hexadecimal, 16).ok(),
+        None => value.parse().ok(),
+    };
+
+    address
+        .filter(|&address| address <= 0x7f)
+        .ok_or_else(|| ArgumentError::InvalidValue(value.to_owned()))
+}
+
+/// Parse a number
+///
+/// # Errors
+///
+/// Return an error if the number is not valid.
+fn parse_number<T>(value: &str) -> Result<T, ArgumentError>
+where
+    T: core::str::FromStr,
+{
+    value
+        .parse()
+        .map_err(|_| ArgumentError::InvalidValue(value.to_owned()))
+}
+
+/// Parse a setting name
+///
+/// # Errors
+///
+/// Return an error if the setting is unknown.
+fn parse_setting(name: &str) -> Result<Setting, ArgumentError> {
+    match name {
+        "temperature-offset" => Ok(Setting::TemperatureOffset),
+        "sensor-altitude" => Ok(Setting::SensorAltitude),
+        "ambient-pressure" => Ok(Setting::AmbientPressure),
+        "automatic-self-calibration" => Ok(Setting::AutomaticSelfCalibration),
+        _ => Err(ArgumentError::UnknownSetting(name.to_owned())),
+    }
+}
+
+/// Parse a value for a setting
+///
+/// # Errors
+///
+/// Return an error if the value is not valid for the setting.
+fn parse_value(setting: Setting, value: &str) -> Result<Value, ArgumentError> {
+    match setting {
+        Setting::TemperatureOffset => Ok(Value::TemperatureOffset(parse_finite(value)?)),
+        Setting::SensorAltitude => Ok(Value::SensorAltitude(parse_finite(value)?)),
+        Setting::AmbientPressure => Ok(Value::AmbientPressure(parse_finite(value)?)),
+        Setting::AutomaticSelfCalibration => match value {
+            "on" | "true" | "1" => Ok(Value::AutomaticSelfCalibration(true)),
+            "off" | "false" | "0" => Ok(Value::AutomaticSelfCalibration(false)),
+            _ => Err(ArgumentError::InvalidValue(value.to_owned())),
+        },
+    }
+}
+
+/// Parse a finite floating-point number
+///
+/// # Errors
+///
+/// Return an error if the number is not valid or not finite.
+fn parse_finite(value: &str) -> Result<f32, ArgumentError> {
+    let number: f32 = parse_number(value)?;
+    if number.is_finite() {
+        Ok(number)
+    } else {
+        Err(ArgumentError::InvalidValue(value.to_owned()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parse arguments from string slices
+    fn parse_strs(arguments: &[&str]) -> Result<Arguments, ArgumentError> {
+        parse(arguments.iter().map(|&argument| argument.to_owned()))
+    }
+
+    /// Parse only the command from string slices
+    fn parse_command_strs(arguments: &[&str]) -> Result<Command, ArgumentError> {
+        parse_strs(arguments).map(|arguments| arguments.command)
+    }
+
+    #[test]
+    fn test_defaults() {
+        let expected = Arguments {
+            bus: PathBuf::from("/dev/i2c-1"),
+            address: 0x62,
+            command: Command::Serial,
+        };
+        assert_eq!(parse_strs(&["serial"]), Ok(expected));
+    }
+
+    #[test]
+    fn test_bus_and_address() {
+        let expected = Arguments {
+            bus: PathBuf::from("/dev/i2c-3"),
+            address: 0x10,
+            command: Command::Persist,
+        };
+        assert_eq!(
+            parse_strs(&["--bus", "/dev/i2c-3", "persist", "-a", "0x10"]),
+            Ok(expected.clone())
+        );
+        assert_eq!(
+            parse_strs(&["-b", "/dev/i2c-3", "--address", "16", "persist"]),
+            Ok(expected)
+        );
+
+        assert_eq!(
+            parse_strs(&["--address", "0x80", "serial"]),
+            Err(ArgumentError::InvalidValue("0x80".to_owned()))
+        );
+        assert_eq!(
+            parse_strs(&["serial", "--bus"]),
+            Err(ArgumentError::MissingValue("--bus"))
+        );
+    }
+
+    #[test]
+    fn test_commands() {
+        assert_eq!(
+            parse_command_strs(&["read"]),
+            Ok(Command::Read {
+                continuous: false,
+                count: None
+            })
+        );
+        assert_eq!(
+            parse_command_strs(&["read", "--continuous", "--count", "3"]),
+            Ok(Command::Read {
+                continuous: true,
+                count: Some(3)
+            })
+        );
+        assert_eq!(parse_command_strs(&["self-test"]), Ok(Command::SelfTest));
+        assert_eq!(parse_command_strs(&["frc", "400"]), Ok(Command::Frc(400.0)));
+        assert_eq!(
+            parse_command_strs(&["factory-reset"]),
+            Ok(Command::FactoryReset)
+        );
+        assert_eq!(parse_command_strs(&["serial", "--help"]), Ok(Command::Help));
+    }
+
+    #[test]
+    fn test_settings() {
+        assert_eq!(
+            parse_command_strs(&["get", "sensor-altitude"]),
+            Ok(Command::Get(Setting::SensorAltitude))
+        );
+        assert_eq!(
+            parse_command_strs(&["set", "temperature-offset", "-1.5"]),
+            Ok(Command::Set(Value::TemperatureOffset(-1.5)))
+        );
+        assert_eq!(
+            parse_command_strs(&["set", "ambient-pressure", "987"]),
+            Ok(Command::Set(Value::AmbientPressure(987.0)))
+        );
+        assert_eq!(
+            parse_command_strs(&["set", "automatic-self-calibration", "off"]),
+            Ok(Command::Set(Value::AutomaticSelfCalibration(false)))
+        );
+
+        assert_eq!(
+            parse_command_strs(&["set", "automatic-self-calibration", "maybe"]),
+            Err(ArgumentError::InvalidValue("maybe".to_owned()))
+        );
+        assert_eq!(
+            parse_command_strs(&["set", "sensor-altitude", "NaN"]),
+            Err(ArgumentError::InvalidValue("NaN".to_owned()))
+        );
+        assert_eq!(
+            parse_command_strs(&["get", "humidity"]),
+            Err(ArgumentError::UnknownSetting("humidity".to_owned()))
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(parse_command_strs(&[]), Err(ArgumentError::MissingCommand));
+        assert_eq!(
+            parse_command_strs(&["calibrate"]),
+            Err(ArgumentError::UnknownCommand("calibrate".to_owned()))
+        );
+        assert_eq!(
+            parse_command_strs(&["frc"]),
+            Err(ArgumentError::WrongArguments("frc".to_owned()))
+        );
+        assert_eq!(
+            parse_command_strs(&["serial", "--verbose"]),
+            Err(ArgumentError::UnknownOption("--verbose".to_owned()))
+        );
+        assert_eq!(
+            parse_command_strs(&["read", "--count", "3"]),
+            Err(ArgumentError::NotContinuous("--count"))
+        );
+        assert_eq!(
+            parse_command_strs(&["serial", "--continuous"]),
+            Err(ArgumentError::NotContinuous("--continuous"))
+        );
+    }
+}
@@ -0,0 +1,478 @@
+// Copyright Claudio Mattera 2024-2026.
+//
+// Distributed under the MIT License or the Apache 2.0 License at your option.
+// See the accompanying files LICENSE-MIT.txt and LICENSE-APACHE-2.0.txt, or
+// online at
+// https://opensource.org/licenses/MIT
+// https://opensource.org/licenses/Apache-2.0
+
+//! Execution of commands on the sensor
+
+use core::fmt;
+use std::io;
+use std::io::Write;
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal::i2c::I2c;
+
+use uom::si::f32::Length;
+use uom::si::f32::Pressure;
+use uom::si::f32::Ratio;
+use uom::si::f32::ThermodynamicTemperature;
+use uom::si::length::meter;
+use uom::si::pressure::hectopascal;
+use uom::si::ratio::part_per_million;
+use uom::si::ratio::percent;
+use uom::si::thermodynamic_temperature::degree_celsius;
+
+use scd4x_rs::Error as Scd4xError;
+use scd4x_rs::Idle;
+use scd4x_rs::Measuring;
+use scd4x_rs::Sample;
+use scd4x_rs::Scd4x;
+
+use crate::arguments::Command;
+use crate::arguments::Setting;
+use crate::arguments::Value;
+
+/// Interval between polls of the data-ready status in milliseconds
+const POLL_INTERVAL_MS: u32 = 100;
+
+/// An error while executing a command
+#[derive(Debug)]
+pub(crate) enum CommandError {
+    /// The sensor could not be queried
+    Sensor(Scd4xError),
+
+    /// The output could not be written
+    Io(io::Error),
+
+    /// The self-test detected a malfunction
+    SelfTestFailed,
+
+    /// The forced recalibration failed
+    RecalibrationFailed,
+}
+
+impl From<Scd4xError> for CommandError {
+    fn from(error: Scd4xError) -> Self {
+        Self::Sensor(error)
+    }
+}
+
+impl From<io::Error> for CommandError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl core::error::Error for CommandError {}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Self::Sensor(ref error) => write!(formatter, "Sensor error: {error}"),
+            Self::Io(ref error) => write!(formatter, "Output error: {error}"),
+            Self::SelfTestFailed => write!(formatter, "Self-test failed"),
+            Self::RecalibrationFailed => write!(formatter, "Forced recalibration failed"),
+        }
+    }
+}
+
+/// Execute a command on the sensor, and write its result to the output
+///
+/// The delay is used for polling the data-ready status during continuous
+/// reads.
+/// Return the sensor in idle state.
+///
+/// # Errors
+///
+/// Return an error if the sensor cannot be queried, if the output cannot be
+/// written, or if the command fails.
+pub(crate) fn run<I2C, D>(
+    command: Command,
+    mut scd4x: Scd4x<I2C, D, Idle>,
+    delay: &mut impl DelayNs,
+    output: &mut impl Write,
+) -> Result<Scd4x<I2C, D, Idle>, CommandError>
+where
+    I2C: I2c,
+    D: DelayNs,
+{
+    match command {
+        Command::Help => {}
+        Command::Serial => {
+            let serial_number = scd4x.get_serial_number()?;
+            writeln!(output, "Serial number: 0x{serial_number:012x}")?;
+        }
+        Command::Read { continuous, count } => {
+            return if continuous {
+                read_periodic(scd4x, count, delay, output)
+            } else {
+                read_single_shot(scd4x, output)
+            };
+        }
+        Command::SelfTest => {
+            if !scd4x.perform_self_test()? {
+                return Err(CommandError::SelfTestFailed);
+            }
+            writeln!(output, "Self-test passed")?;
+        }
+        Command::Frc(ppm) => {
+            let reference = Ratio::new::<part_per_million>(ppm);
+            let correction = scd4x
+                .perform_forced_recalibration(reference)?
+                .ok_or(CommandError::RecalibrationFailed)?;
+            writeln!(
+                output,
+                "Forced recalibration correction: {:.0} ppm",
+                correction.get::<part_per_million>()
+            )?;
+        }
+        Command::Get(setting) => get(&mut scd4x, setting, output)?,
+        Command::Set(value) => set(&mut scd4x, value, output)?,
+        Command::Persist => {
+            scd4x.persist_settings()?;
+            writeln!(output, "Settings persisted")?;
+        }
+        Command::FactoryReset => {
+            scd4x.perform_factory_reset()?;
+            writeln!(output, "Factory settings restored")?;
+        }
+    }
+
+    Ok(scd4x)
+}
+
+/// Read a single-shot measurement
+///
+/// # Errors
+///
+/// Return an error if the sensor cannot be queried or if the output cannot be
+/// written.
+fn read_single_shot<I2C, D>(
+    scd4x: Scd4x<I2C, D, Idle>,
+    output: &mut impl Write,
+) -> Result<Scd4x<I2C, D, Idle>, CommandError>
+where
+    I2C: I2c,
+    D: DelayNs,
+{
+    let mut scd4x = scd4x.measure_single_shot()?;
+    let sample = scd4x.read_measurement();
+    let scd4x = scd4x.stop_periodic_measurement()?;
+
+    write_sample(output, &sample?)?;
+
+    Ok(scd4x)
+}
+
+/// Read periodic measurements, forever or a number of times
+///
+/// Periodic measurements are stopped before returning, also in case of errors.
+///
+/// # Errors
+///
+/// Return an error if the sensor cannot be queried or if the output cannot be
+/// written.
+fn read_periodic<I2C, D>(
+    scd4x: Scd4x<I2C, D, Idle>,
+    count: Option<u32>,
+    delay: &mut impl DelayNs,
+    output: &mut impl Write,
+) -> Result<Scd4x<I2C, D, Idle>, CommandError>
+where
+    I2C: I2c,
+    D: DelayNs,
+{
+    let mut scd4x = scd4x.start_periodic_measurement()?;
+
+    let mut remaining = count;
+    let result = loop {
+        if remaining == Some(0) {
+            break Ok(());
+        }
+
+        if let Err(error) = read_next_sample(&mut scd4x, delay, output) {
+            break Err(error);
+        }
+
+        remaining = remaining.map(|remaining| remaining.saturating_sub(1));
+    };
+
+    let scd4x = scd4x.stop_periodic_measurement()?;
+    result?;
+
+    Ok(scd4x)
+}
+
+/// Wait for the next periodic measurement and write it to the output
+///
+/// # Errors
+///
+/// Return an error if the sensor cannot be queried or if the output cannot be
+/// written.
+fn read_next_sample<I2C, D>(
+    scd4x: &mut Scd4x<I2C, D, Measuring>,
+    delay: &mut impl DelayNs,
+    output: &mut impl Write,
+) -> Result<(), CommandError>
+where
+    I2C: I2c,
+    D: DelayNs,
+{
+    while !scd4x.get_data_ready_status()? {
+        delay.delay_ms(POLL_INTERVAL_MS);
+    }
+
+    let sample = scd4x.read_measurement()?;
+    write_sample(output, &sample)?;
+    output.flush()?;
+
+    Ok(())
+}
+
+/// Write a sample to the output
+///
+/// # Errors
+///
+/// Return an error if the output cannot be written.
+fn write_sample(output: &mut impl Write, sample: &Sample) -> io::Result<()> {
+    writeln!(
+        output,
+        "CO₂: {:.0} ppm, temperature: {:.2} °C, relative humidity: {:.2} %",
+        sample.co2.get::<part_per_million>(),
+        sample.temperature.get::<degree_celsius>(),
+        sample.humidity.get::<percent>(),
+    )
+}
+
+/// Read a setting and write it to the output
+///
+/// # Errors
+///
+/// Return an error if the sensor cannot be queried, or if the output cannot
+/// be written.
+fn get<I2C, D>(
+    scd4x: &mut Scd4x<I2C, D, Idle>,
+    setting: Setting,
+    output: &mut impl Write,
+) -> Result<(), CommandError>
+where
+    I2C: I2c,
+    D: DelayNs,
+{
+    match setting {
+        Setting::TemperatureOffset => {
+            let offset = scd4x.get_temperature_offset()?;
+            writeln!(
+                output,
+                "Temperature offset: {:.2} °C",
+                offset.get::<degree_celsius>()
+            )?;
+        }
+        Setting::SensorAltitude => {
+            let altitude = scd4x.get_sensor_altitude()?;
+            writeln!(output, "Sensor altitude: {:.0} m", altitude.get::<meter>())?;
+        }
+        Setting::AmbientPressure => {
+            let pressure = scd4x.get_ambient_pressure()?;
+            writeln!(
+                output,
+                "Ambient pressure: {:.0} hPa",
+                pressure.get::<hectopascal>()
+            )?;
+        }
+        Setting::AutomaticSelfCalibration => {
+            let enabled = scd4x.get_automatic_self_calibration_enabled()?;
+            writeln!(
+                output,
+                "Automatic self-calibration: {}",
+                if enabled { "on" } else { "off" }
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Change a setting and write it to the output
+///
+/// # Errors
+///
+/// Return an error if the sensor cannot be queried or if the output cannot be
+/// written.
+fn set<I2C, D>(
+    scd4x: &mut Scd4x<I2C, D, Idle>,
+    value: Value,
+    output: &mut impl Write,
+) -> Result<(), CommandError>
+where
+    I2C: I2c,
+    D: DelayNs,
+{
+    match value {
+        Value::TemperatureOffset(celsius) => {
+            scd4x
+                .set_temperature_offset(ThermodynamicTemperature::new::<degree_celsius>(celsius))?;
+            writeln!(output, "Temperature offset set to {celsius} °C")?;
+        }
+        Value::SensorAltitude(meters) => {
+            scd4x.set_sensor_altitude(Length::new::<meter>(meters))?;
+            writeln!(output, "Sensor altitude set to {meters} m")?;
+        }
+        Value::AmbientPressure(hectopascals) => {
+            scd4x.set_ambient_pressure(Pressure::new::<hectopascal>(hectopascals))?;
+            writeln!(output, "Ambient pressure set to {hectopascals} hPa")?;
+        }
+        Value::AutomaticSelfCalibration(enabled) => {
+            scd4x.set_automatic_self_calibration_enabled(enabled)?;
+            writeln!(
+                output,
+                "Automatic self-calibration set to {}",
+                if enabled { "on" } else { "off" }
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    #![expect(clippy::panic_in_result_fn, reason = "Assertions in tests")]
+
+    use super::*;
+
+    use scd4x_rs::sim::Fault;
+    use scd4x_rs::sim::FaultInjector;
+    use scd4x_rs::sim::Simulator;
+    use scd4x_rs::sim::VirtualClock;
+
+    /// Constant conditions measured by the simulator
+    fn environment() -> Sample {
+        Sample {
+            co2: Ratio::new::<part_per_million>(600.0),
+            temperature: ThermodynamicTemperature::new::<degree_celsius>(21.0),
+            humidity: Ratio::new::<percent>(40.0),
+        }
+    }
+
+    /// Run commands on a simulated sensor, and return their output
+    fn run_all(commands: &[Command]) -> Result<String, CommandError> {
+        let clock = VirtualClock::new();
+        let mut simulator = Simulator::new(&clock, environment());
+        let mut scd4x = Scd4x::new(&mut simulator, &clock);
+
+        let mut output = Vec::new();
+        for &command in commands {
+            scd4x = run(command, scd4x, &mut &clock, &mut output)?;
+        }
+
+        Ok(String::from_utf8_lossy(&output).into_owned())
+    }
+
+    #[test]
+    fn test_serial() -> Result<(), CommandError> {
+        let output = run_all(&[Command::Serial])?;
+        assert_eq!(output, "Serial number: 0xf8969f073bbe\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_read() -> Result<(), CommandError> {
+        let line = "CO₂: 600 ppm, temperature: 21.00 °C, relative humidity: 40.00 %\n";
+
+        let output = run_all(&[Command::Read {
+            continuous: false,
+            count: None,
+        }])?;
+        assert_eq!(output, line);
+
+        let output = run_all(&[Command::Read {
+            continuous: true,
+            count: Some(3),
+        }])?;
+        assert_eq!(output, line.repeat(3));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_self_test() -> Result<(), CommandError> {
+        let output = run_all(&[Command::SelfTest])?;
+        assert_eq!(output, "Self-test passed\n");
+
+        let clock = VirtualClock::new();
+        let simulator = Simulator::new(&clock, environment());
+        let injector = FaultInjector::new(simulator, |_| Some(Fault::SelfTestFailure(1)));
+        let scd4x = Scd4x::new(injector, &clock);
+
+        let result = run(Command::SelfTest, scd4x, &mut &clock, &mut Vec::new());
+        assert!(matches!(result, Err(CommandError::SelfTestFailed)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_frc() -> Result<(), CommandError> {
+        let result = run_all(&[Command::Frc(400.0)]);
+        assert!(matches!(result, Err(CommandError::RecalibrationFailed)));
+
+        let read = Command::Read {
+            continuous: false,
+            count: None,
+        };
+        let output = run_all(&[read, Command::Frc(400.0)])?;
+        let expected = "Forced recalibration correction: -200 ppm\n";
+        assert!(output.ends_with(expected), "{output}");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_settings() -> Result<(), CommandError> {
+        let output = run_all(&[
+            Command::Set(Value::TemperatureOffset(5.5)),
+            Command::Get(Setting::TemperatureOffset),
+            Command::Set(Value::SensorAltitude(1100.0)),
+            Command::Get(Setting::SensorAltitude),
+            Command::Set(Value::AmbientPressure(987.0)),
+            Command::Get(Setting::AmbientPressure),
+            Command::Set(Value::AutomaticSelfCalibration(false)),
+            Command::Get(Setting::AutomaticSelfCalibration),
+        ])?;
+        let expected = "\
+Temperature offset set to 5.5 °C
+Temperature offset: 5.50 °C
+Sensor altitude set to 1100 m
+Sensor altitude: 1100 m
+Ambient pressure set to 987 hPa
+Ambient pressure: 987 hPa
+Automatic self-calibration set to off
+Automatic self-calibration: off
+";
+        assert_eq!(output, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_persist_and_factory_reset() -> Result<(), CommandError> {
+        let output = run_all(&[
+            Command::Set(Value::SensorAltitude(1100.0)),
+            Command::Persist,
+            Command::FactoryReset,
+            Command::Get(Setting::SensorAltitude),
+        ])?;
+        let expected = "\
+Sensor altitude set to 1100 m
+Settings persisted
+Factory settings restored
+Sensor altitude: 0 m
+";
+        assert_eq!(output, expected);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+// Copyright Claudio Mattera 2024-2026.
+//
+// Distributed under the MIT License or the Apache 2.0 License at your option.
+// See the accompanying files LICENSE-MIT.txt and LICENSE-APACHE-2.0.txt, or
+// online at
+// https://opensource.org/licenses/MIT
+// https://opensource.org/licenses/Apache-2.0
+
+//! Command-line tool for commissioning SCD4x sensors on Linux
+//!
+//! The tool queries a sensor attached to a Linux I²C bus, e.g. `/dev/i2c-1`
+//! on a Raspberry Pi.
+//! It can print the serial number and measurements, perform self-tests and
+//! forced recalibrations, and read, change and persist settings.
+//! Run `scd4x --help` for the list of commands.
+
+mod arguments;
+mod commands;
+
+use core::error::Error;
+use std::env::args;
+use std::io::stdout;
+use std::process::ExitCode;
+
+use linux_embedded_hal::Delay;
+use linux_embedded_hal::I2cdev;
+
+use scd4x_rs::Scd4x;
+
+use self::arguments::parse;
+use self::arguments::Arguments;
+use self::arguments::Command;
+use self::arguments::USAGE;
+use self::commands::run;
+
+/// Main entry point
+fn main() -> ExitCode {
+    let arguments = match parse(args().skip(1)) {
+        Ok(arguments) => arguments,
+        Err(error) => {
+            eprintln!("Error: {error}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    if arguments.command == Command::Help {
+        print!("{USAGE}");
+        return ExitCode::SUCCESS;
+    }
+
+    match execute(&arguments) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("Error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Open the I²C bus and execute the command
+///
+/// Periodic measurements left running by other programs are stopped first.
+fn execute(arguments: &Arguments) -> Result<(), Box<dyn Error>> {
+    let i2c = I2cdev::new(&arguments.bus)
+        .map_err(|error| format!("Cannot open {}: {error}", arguments.bus.display()))?;
+
+    let scd4x = Scd4x::new_in_measuring_with_address(i2c, arguments.address, Delay);
+    let scd4x = scd4x.stop_periodic_measurement()?;
+
+    let _scd4x = run(arguments.command, scd4x, &mut Delay, &mut stdout().lock())?;
+
+    Ok(())
+}
@@ -0,0 +1,282 @@
+// Copyright Claudio Mattera 2024-2026.
+//
+// Distributed under the MIT License or the Apache 2.0 License at your option.
+// See the accompanying files LICENSE-MIT.txt and LICENSE-APACHE-2.0.txt, or
+// online at
+// https://opensource.org/licenses/MIT
+// https://opensource.org/licenses/Apache-2.0
+
+//! Data types and functions for indoor air-quality classification
+//!
+//! A CO₂ concentration is classified in one of the categories of
+//! [`AirQuality`] according to a set of [`Thresholds`].
+//! A [`Classifier`] additionally applies hysteresis, so that readings
+//! fluctuating around a threshold do not flap between two categories.
+//!
+//! ~~~~
+//! use scd4x_rs::air_quality::AirQuality;
+//! use scd4x_rs::air_quality::Classifier;
+//! use scd4x_rs::air_quality::Thresholds;
+//! # #[cfg(not(feature = "uom"))]
+//! # {
+//!
+//! let mut classifier = Classifier::new(Thresholds::en_13779());
+//!
+//! assert_eq!(classifier.update(1010.0), AirQuality::Poor);
+//! assert_eq!(classifier.update(990.0), AirQuality::Poor);
+//! assert_eq!(classifier.update(940.0), AirQuality::Moderate);
+//! # }
+//! ~~~~
+
+use crate::sample::co2_from_ppm;
+use crate::Co2;
+
+/// Indoor air-quality category
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AirQuality {
+    /// Good air quality
+    Good,
+
+    /// Moderate air quality
+    Moderate,
+
+    /// Poor air quality
+    Poor,
+
+    /// Bad air quality
+    Bad,
+}
+
+/// Thresholds between air-quality categories
+///
+/// A CO₂ concentration equal to a threshold belongs to the higher category.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Thresholds {
+    /// Lowest CO₂ concentration for moderate air quality
+    pub moderate: Co2,
+
+    /// Lowest CO₂ concentration for poor air quality
+    pub poor: Co2,
+
+    /// Lowest CO₂ concentration for bad air quality
+    pub bad: Co2,
+
+    /// Decrease below a threshold required to move to a lower category
+    pub hysteresis: Co2,
+}
+
+impl Thresholds {
+    /// Create thresholds following the indoor air classes of EN 13779
+    ///
+    /// The classes IDA 1 to IDA 4 are defined relative to the outdoor CO₂
+    /// concentration, which is assumed to be 400 ppm.
+    /// Air quality is good below 800 ppm, moderate below 1000 ppm, poor below
+    /// 1400 ppm and bad above, with a hysteresis of 50 ppm.
+    #[must_use]
+    pub fn en_13779() -> Self {
+        Self {
+            moderate: co2_from_ppm(800.0),
+            poor: co2_from_ppm(1000.0),
+            bad: co2_from_ppm(1400.0),
+            hysteresis: co2_from_ppm(50.0),
+        }
+    }
+
+    /// Create thresholds following the guidance of the German Federal
+    /// Environment Agency (Umweltbundesamt)
+    ///
+    /// The guidance considers concentrations below 1000 ppm harmless, between
+    /// 1000 ppm and 2000 ppm elevated, and above 2000 ppm unacceptable.
+    /// The elevated range is split into moderate and poor air quality at
+    /// 1500 ppm, and the hysteresis is 50 ppm.
+    #[must_use]
+    pub fn uba() -> Self {
+        Self {
+            moderate: co2_from_ppm(1000.0),
+            poor: co2_from_ppm(1500.0),
+            bad: co2_from_ppm(2000.0),
+            hysteresis: co2_from_ppm(50.0),
+        }
+    }
+
+    /// Classify a CO₂ concentration, without hysteresis
+    #[must_use]
+    pub fn classify(&self, co2: Co2) -> AirQuality {
+        if co2 >= self.bad {
+            AirQuality::Bad
+        } else if co2 >= self.poor {
+            AirQuality::Poor
+        } else if co2 >= self.moderate {
+            AirQuality::Moderate
+        } else {
+            AirQuality::Good
+        }
+    }
+
+    /// Classify a CO₂ concentration, excluding the thresholds from the upper
+    /// categories
+    fn classify_exclusive(&self, co2: Co2) -> AirQuality {
+        if co2 > self.bad {
+            AirQuality::Bad
+        } else if co2 > self.poor {
+            AirQuality::Poor
+        } else if co2 > self.moderate {
+            AirQuality::Moderate
+        } else {
+            AirQuality::Good
+        }
+    }
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self::en_13779()
+    }
+}
+
+/// Air-quality classifier with hysteresis
+///
+/// The category moves up as soon as a threshold is reached, but it only moves
+/// down once the CO₂ concentration drops below the threshold by at least the
+/// hysteresis.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Classifier {
+    /// Thresholds between categories
+    thresholds: Thresholds,
+
+    /// Current category, if any concentration was classified
+    current: Option<AirQuality>,
+}
+
+impl Classifier {
+    /// Create a new classifier
+    #[must_use]
+    pub fn new(thresholds: Thresholds) -> Self {
+        Self {
+            thresholds,
+            current: None,
+        }
+    }
+
+    /// Return the thresholds between categories
+    #[must_use]
+    pub fn thresholds(&self) -> &Thresholds {
+        &self.thresholds
+    }
+
+    /// Return the current category, if any concentration was classified
+    #[must_use]
+    pub fn current(&self) -> Option<AirQuality> {
+        self.current
+    }
+
+    /// Classify a CO₂ concentration, and update the current category
+    pub fn update(&mut self, co2: Co2) -> AirQuality {
+        let upper = self.thresholds.classify(co2);
+        let lower = self
+            .thresholds
+            .classify_exclusive(co2 + self.thresholds.hysteresis);
+
+        let category = match self.current {
+            Some(current) => current.max(upper).min(lower),
+            None => upper,
+        };
+
+        self.current = Some(category);
+        category
+    }
+
+    /// Forget the current category
+    pub fn reset(&mut self) {
+        self.current = None;
+    }
+}
+
+impl Default for Classifier {
+    fn default() -> Self {
+        Self::new(Thresholds::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        let thresholds = Thresholds::en_13779();
+
+        let cases = [
+            (400.0, AirQuality::Good),
+            (799.0, AirQuality::Good),
+            (800.0, AirQuality::Moderate),
+            (999.0, AirQuality::Moderate),
+            (1000.0, AirQuality::Poor),
+            (1399.0, AirQuality::Poor),
+            (1400.0, AirQuality::Bad),
+            (5000.0, AirQuality::Bad),
+        ];
+
+        for (ppm, expected) in cases {
+            let actual = thresholds.classify(co2_from_ppm(ppm));
+            assert_eq!(actual, expected, "{ppm} ppm");
+        }
+    }
+
+    #[test]
+    fn test_classify_uba() {
+        let thresholds = Thresholds::uba();
+
+        assert_eq!(thresholds.classify(co2_from_ppm(999.0)), AirQuality::Good);
+        assert_eq!(thresholds.classify(co2_from_ppm(1500.0)), AirQuality::Poor);
+        assert_eq!(thresholds.classify(co2_from_ppm(2000.0)), AirQuality::Bad);
+    }
+
+    #[test]
+    fn test_hysteresis() {
+        let mut classifier = Classifier::default();
+        assert_eq!(classifier.current(), None);
+
+        let cases = [
+            (790.0, AirQuality::Good),
+            (805.0, AirQuality::Moderate),
+            (795.0, AirQuality::Moderate),
+            (810.0, AirQuality::Moderate),
+            (760.0, AirQuality::Moderate),
+            (749.0, AirQuality::Good),
+            (790.0, AirQuality::Good),
+            (1450.0, AirQuality::Bad),
+            (1000.0, AirQuality::Poor),
+            (700.0, AirQuality::Good),
+        ];
+
+        for (ppm, expected) in cases {
+            let actual = classifier.update(co2_from_ppm(ppm));
+            assert_eq!(actual, expected, "{ppm} ppm");
+        }
+
+        assert_eq!(classifier.current(), Some(AirQuality::Good));
+    }
+
+    #[test]
+    fn test_hysteresis_boundary() {
+        let mut classifier = Classifier::default();
+
+        assert_eq!(classifier.update(co2_from_ppm(820.0)), AirQuality::Moderate);
+        assert_eq!(classifier.update(co2_from_ppm(750.1)), AirQuality::Moderate);
+        assert_eq!(classifier.update(co2_from_ppm(750.0)), AirQuality::Good);
+        assert_eq!(classifier.update(co2_from_ppm(799.0)), AirQuality::Good);
+        assert_eq!(classifier.update(co2_from_ppm(800.0)), AirQuality::Moderate);
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut classifier = Classifier::default();
+
+        assert_eq!(classifier.update(co2_from_ppm(820.0)), AirQuality::Moderate);
+
+        classifier.reset();
+        assert_eq!(classifier.current(), None);
+        assert_eq!(classifier.update(co2_from_ppm(780.0)), AirQuality::Good);
+    }
+}
@@ -0,0 +1,196 @@
+// Copyright Claudio Mattera 2024-2026.
+//
+// Distributed under the MIT License or the Apache 2.0 License at your option.
+// See the accompanying files LICENSE-MIT.txt and LICENSE-APACHE-2.0.txt, or
+// online at
+// https://opensource.org/licenses/MIT
+// https://opensource.org/licenses/Apache-2.0
+
+//! Functions for converting between altitude and pressure
+//!
+//! The sensor can be compensated for ambient pressure either by setting its
+//! altitude or by setting the ambient pressure directly.
+//! When both are set, the ambient pressure takes precedence over the altitude.
+//!
+//! The conversions use the barometric formula of the International Standard
+//! Atmosphere, which is valid in the troposphere, i.e. up to 11 km above sea
+//! level.
+//! The actual ambient pressure depends on the weather, and it typically
+//! deviates from the standard one by a few tens of hectoPascal.
+//!
+//! ~~~~
+//! use scd4x_rs::atmosphere::altitude_at_pressure;
+//! use scd4x_rs::atmosphere::pressure_at_altitude;
+//! # #[cfg(not(feature = "uom"))]
+//! # {
+//!
+//! let pressure = pressure_at_altitude(1000.0);
+//! assert!((pressure - 898.75).abs() < 0.01);
+//!
+//! let altitude = altitude_at_pressure(pressure);
+//! assert!((altitude - 1000.0).abs() < 0.1);
+//! # }
+//! ~~~~
+
+use libm::fabsf;
+use libm::powf;
+
+use crate::sample::altitude_from_meter;
+use crate::sample::hectopascal_from_pressure;
+use crate::sample::meter_from_altitude;
+use crate::sample::pressure_from_hectopascal;
+use crate::Altitude;
+use crate::Pressure;
+
+/// Standard pressure at sea level in hectoPascal
+const SEA_LEVEL_PRESSURE: f32 = 1013.25;
+
+/// Ratio of the temperature lapse rate and the temperature at sea level in
+/// 1 / m
+const LAPSE_RATE_OVER_TEMPERATURE: f32 = 2.255_77e-5;
+
+/// Exponent of the barometric formula (dimensionless)
+const BAROMETRIC_EXPONENT: f32 = 5.255_88;
+
+/// A disagreement between sensor altitude and ambient pressure
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PressureMismatch {
+    /// Standard-atmosphere pressure at the sensor altitude
+    pub expected: Pressure,
+
+    /// Ambient pressure
+    pub actual: Pressure,
+}
+
+impl core::error::Error for PressureMismatch {}
+
+impl core::fmt::Display for PressureMismatch {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(formatter, "{self:?}")
+    }
+}
+
+/// Compute the standard-atmosphere pressure at an altitude
+#[must_use]
+pub fn pressure_at_altitude(altitude: Altitude) -> Pressure {
+    let meter = meter_from_altitude(altitude);
+
+    let hectopascal = SEA_LEVEL_PRESSURE
+        * powf(
+            1.0 - LAPSE_RATE_OVER_TEMPERATURE * meter,
+            BAROMETRIC_EXPONENT,
+        );
+
+    pressure_from_hectopascal(hectopascal)
+}
+
+/// Compute the altitude at which the standard-atmosphere pressure is equal to
+/// a pressure
+#[must_use]
+pub fn altitude_at_pressure(pressure: Pressure) -> Altitude {
+    let ratio = hectopascal_from_pressure(pressure) / SEA_LEVEL_PRESSURE;
+
+    let meter = (1.0 - powf(ratio, 1.0 / BAROMETRIC_EXPONENT)) / LAPSE_RATE_OVER_TEMPERATURE;
+
+    altitude_from_meter(meter)
+}
+
+/// Check that an ambient pressure agrees with a sensor altitude
+///
+/// The ambient pressure agrees with the altitude if it differs from the
+/// standard-atmosphere pressure at that altitude by at most the tolerance.
+/// Since the ambient pressure changes with the weather, a tolerance of about
+/// 50 hPa avoids false alarms.
+///
+/// # Errors
+///
+/// Return an error if the ambient pressure does not agree with the altitude.
+pub fn check_consistency(
+    altitude: Altitude,
+    ambient_pressure: Pressure,
+    tolerance: Pressure,
+) -> Result<(), PressureMismatch> {
+    let expected = pressure_at_altitude(altitude);
+
+    let difference =
+        hectopascal_from_pressure(ambient_pressure) - hectopascal_from_pressure(expected);
+
+    if fabsf(difference) <= hectopascal_from_pressure(tolerance) {
+        Ok(())
+    } else {
+        Err(PressureMismatch {
+            expected,
+            actual: ambient_pressure,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Standard-atmosphere pressure in hectoPascal for altitude in meter
+    const PRESSURE_TABLE: [(f32, f32); 6] = [
+        (-400.0, 1062.2),
+        (0.0, 1013.25),
+        (500.0, 954.6),
+        (1000.0, 898.7),
+        (2000.0, 795.0),
+        (5000.0, 540.2),
+    ];
+
+    #[test]
+    fn test_pressure_at_altitude() {
+        for (meter, expected) in PRESSURE_TABLE {
+            let actual =
+                hectopascal_from_pressure(pressure_at_altitude(altitude_from_meter(meter)));
+            assert!(
+                (actual - expected).abs() < 0.1,
+                "{meter} m: {actual} != {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_altitude_at_pressure() {
+        for (expected, hectopascal) in PRESSURE_TABLE {
+            let actual =
+                meter_from_altitude(altitude_at_pressure(pressure_from_hectopascal(hectopascal)));
+            assert!(
+                (actual - expected).abs() < 1.0,
+                "{hectopascal} hPa: {actual} != {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for meter in [0.0, 250.0, 1100.0, 1950.0, 3000.0] {
+            let pressure = pressure_at_altitude(altitude_from_meter(meter));
+            let actual = meter_from_altitude(altitude_at_pressure(pressure));
+            assert!((actual - meter).abs() < 0.1, "{actual} != {meter}");
+        }
+    }
+
+    #[test]
+    fn test_check_consistency() {
+        let altitude = altitude_from_meter(1100.0);
+        let tolerance = pressure_from_hectopascal(50.0);
+
+        let result = check_consistency(altitude, pressure_from_hectopascal(870.0), tolerance);
+        assert_eq!(result, Ok(()));
+
+        let result = check_consistency(altitude, pressure_from_hectopascal(1013.0), tolerance);
+        let actual = result.map_or_else(
+            |mismatch| hectopascal_from_pressure(mismatch.actual),
+            |()| f32::NAN,
+        );
+        let expected = result.map_or_else(
+            |mismatch| hectopascal_from_pressure(mismatch.expected),
+            |()| f32::NAN,
+        );
+
+        assert!((actual - 1013.0).abs() < 0.001, "{actual} != 1013");
+        assert!((expected - 887.9).abs() < 0.1, "{expected} != 887.9");
+    }
+}
@@ -8,10 +8,11 @@
 
 //! Data types and functions for blocking SCD4x sensor interface
 
-mod command;
-use self::command::Command;
+mod compensation;
+pub use self::compensation::PressureCompensator;
+pub use self::compensation::PressureSource;
 
-mod commands;
+mod execution;
 
 mod sensor;
 pub use self::sensor::Scd4x;
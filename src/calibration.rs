@@ -0,0 +1,273 @@
+// Copyright Claudio Mattera 2024-2026.
+//
+// Distributed under the MIT License or the Apache 2.0 License at your option.
+// See the accompanying files LICENSE-MIT.txt and LICENSE-APACHE-2.0.txt, or
+// online at
+// https://opensource.org/licenses/MIT
+// https://opensource.org/licenses/Apache-2.0
+
+//! Data types and functions for calibrating the sensor
+//!
+//! The temperature measured by the sensor is affected by self-heating and by
+//! the enclosure.
+//! The datasheet recommends compensating for it by setting a temperature
+//! offset, computed from a reference temperature:
+//!
+//! T<sub>offset</sub> = T<sub>SCD4x</sub> − T<sub>reference</sub> + T<sub>offset, previous</sub>
+//!
+//! A [`TemperatureOffsetEstimator`] averages this value over several pairs of
+//! measured and reference temperatures.
+//!
+//! ~~~~
+//! use scd4x_rs::calibration::TemperatureOffsetEstimator;
+//! # #[cfg(not(feature = "uom"))]
+//! # {
+//!
+//! let mut estimator = TemperatureOffsetEstimator::new(4.0);
+//!
+//! estimator.add(25.4, 23.9);
+//! estimator.add(25.6, 24.1);
+//!
+//! assert_eq!(estimator.offset(), Some(5.5));
+//! # }
+//! ~~~~
+
+use crate::psychrometrics::relative_humidity_at;
+use crate::sample::celsius_from_temperature;
+use crate::sample::temperature_from_celsius;
+use crate::Sample;
+use crate::Temperature;
+
+/// Lowest temperature offset supported by the sensor in Celsius
+const MIN_OFFSET: f32 = 0.0;
+
+/// Highest temperature offset supported by the sensor in Celsius
+const MAX_OFFSET: f32 = 20.0;
+
+/// An estimator of the temperature offset from reference temperatures
+///
+/// Temperature offsets are expressed as temperatures in Celsius, like for the
+/// function `set_temperature_offset()` of the sensor.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TemperatureOffsetEstimator {
+    /// Temperature offset set when the temperatures were measured, in Celsius
+    current_offset: f32,
+
+    /// Sum of the differences between measured and reference temperatures
+    sum_of_differences: f32,
+
+    /// Number of pairs of temperatures
+    count: u16,
+}
+
+impl TemperatureOffsetEstimator {
+    /// Create a new estimator
+    ///
+    /// The current offset is the one set on the sensor while measuring
+    /// temperatures, as returned by the function `get_temperature_offset()` of
+    /// the sensor.
+    #[must_use]
+    pub fn new(current_offset: Temperature) -> Self {
+        Self {
+            current_offset: celsius_from_temperature(current_offset),
+            sum_of_differences: 0.0,
+            count: 0,
+        }
+    }
+
+    /// Return the number of pairs of temperatures
+    #[must_use]
+    pub fn len(&self) -> u16 {
+        self.count
+    }
+
+    /// Return whether no pair of temperatures was added
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Add a pair of temperatures measured by the sensor and by a reference
+    /// thermometer at the same time
+    pub fn add(&mut self, measured: Temperature, reference: Temperature) {
+        let difference = celsius_from_temperature(measured) - celsius_from_temperature(reference);
+
+        self.sum_of_differences += difference;
+        self.count = self.count.saturating_add(1);
+    }
+
+    /// Return the recommended temperature offset
+    ///
+    /// The offset is clamped to the range supported by the sensor, from 0 °C to
+    /// 20 °C.
+    /// Return `None` if no pair of temperatures was added.
+    #[must_use]
+    pub fn offset(&self) -> Option<Temperature> {
+        self.offset_celsius().map(temperature_from_celsius)
+    }
+
+    /// Re-express a sample measured with the current offset as if it was
+    /// measured with the recommended offset
+    ///
+    /// The temperature is shifted by the change in offset, and the humidity is
+    /// corrected for the change in temperature at constant absolute humidity.
+    /// The CO₂ concentration is unchanged.
+    /// Return `None` if no pair of temperatures was added.
+    #[must_use]
+    pub fn correct(&self, sample: &Sample) -> Option<Sample> {
+        let offset = self.offset_celsius()?;
+
+        Some(recompensate(
+            sample,
+            temperature_from_celsius(self.current_offset),
+            temperature_from_celsius(offset),
+        ))
+    }
+
+    /// Forget all pairs of temperatures
+    pub fn reset(&mut self) {
+        self.sum_of_differences = 0.0;
+        self.count = 0;
+    }
+
+    /// Return the recommended temperature offset in Celsius
+    fn offset_celsius(&self) -> Option<f32> {
+        (self.count > 0).then(|| {
+            let mean_difference = self.sum_of_differences / f32::from(self.count);
+            (self.current_offset + mean_difference).clamp(MIN_OFFSET, MAX_OFFSET)
+        })
+    }
+}
+
+/// Re-express a sample recorded with a temperature offset as if it was
+/// recorded with another temperature offset
+///
+/// The temperature is shifted by the difference between the offsets, and the
+/// humidity is corrected for the change in temperature at constant absolute
+/// humidity, using the Magnus formula.
+/// The CO₂ concentration is unchanged.
+#[must_use]
+pub fn recompensate(sample: &Sample, old_offset: Temperature, new_offset: Temperature) -> Sample {
+    let celsius = celsius_from_temperature(sample.temperature);
+    let shift = celsius_from_temperature(new_offset) - celsius_from_temperature(old_offset);
+
+    let temperature = temperature_from_celsius(celsius - shift);
+    let humidity = relative_humidity_at(sample.temperature, sample.humidity, temperature);
+
+    Sample {
+        co2: sample.co2,
+        temperature,
+        humidity,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::sample::co2_from_ppm;
+    use crate::sample::humidity_from_number;
+    use crate::sample::number_from_humidity;
+    use crate::sample::ppm_from_co2;
+
+    /// Return a temperature offset in Celsius, or NaN if not available
+    fn offset_celsius(estimator: &TemperatureOffsetEstimator) -> f32 {
+        estimator
+            .offset()
+            .map_or(f32::NAN, celsius_from_temperature)
+    }
+
+    #[test]
+    fn test_offset() {
+        let mut estimator = TemperatureOffsetEstimator::new(temperature_from_celsius(4.0));
+        assert!(estimator.is_empty());
+        assert_eq!(estimator.offset(), None);
+
+        let pairs = [(25.3, 22.9), (25.6, 23.0), (25.9, 23.5), (26.2, 23.6)];
+        for (measured, reference) in pairs {
+            estimator.add(
+                temperature_from_celsius(measured),
+                temperature_from_celsius(reference),
+            );
+        }
+
+        let offset = offset_celsius(&estimator);
+        assert_eq!(estimator.len(), 4);
+        assert!((offset - 6.5).abs() < 0.001, "{offset} != 6.5");
+    }
+
+    #[test]
+    fn test_offset_is_clamped() {
+        let mut estimator = TemperatureOffsetEstimator::new(temperature_from_celsius(1.0));
+        estimator.add(
+            temperature_from_celsius(20.0),
+            temperature_from_celsius(23.0),
+        );
+
+        let offset = offset_celsius(&estimator);
+        assert!(offset.abs() < 0.001, "{offset} != 0");
+
+        estimator.reset();
+        assert_eq!(estimator.offset(), None);
+    }
+
+    #[test]
+    fn test_correct() {
+        let mut estimator = TemperatureOffsetEstimator::new(temperature_from_celsius(4.0));
+        estimator.add(
+            temperature_from_celsius(25.0),
+            temperature_from_celsius(22.5),
+        );
+
+        let sample = Sample {
+            co2: co2_from_ppm(800.0),
+            temperature: temperature_from_celsius(25.0),
+            humidity: humidity_from_number(50.0),
+        };
+
+        let corrected = estimator.correct(&sample);
+        let co2 = corrected.map_or(f32::NAN, |sample| ppm_from_co2(sample.co2));
+        let celsius = corrected.map_or(f32::NAN, |sample| {
+            celsius_from_temperature(sample.temperature)
+        });
+        let percent = corrected.map_or(f32::NAN, |sample| number_from_humidity(sample.humidity));
+
+        assert!((co2 - 800.0).abs() < 0.001, "{co2} != 800");
+        assert!((celsius - 22.5).abs() < 0.001, "{celsius} != 22.5");
+        assert!((percent - 57.63).abs() < 0.01, "{percent} != 57.63");
+    }
+
+    #[test]
+    fn test_recompensate() {
+        let sample = Sample {
+            co2: co2_from_ppm(650.0),
+            temperature: temperature_from_celsius(21.0),
+            humidity: humidity_from_number(45.0),
+        };
+
+        let recompensated = recompensate(
+            &sample,
+            temperature_from_celsius(4.0),
+            temperature_from_celsius(2.5),
+        );
+
+        let celsius = celsius_from_temperature(recompensated.temperature);
+        let percent = number_from_humidity(recompensated.humidity);
+
+        assert!((ppm_from_co2(recompensated.co2) - 650.0).abs() < 0.001);
+        assert!((celsius - 22.5).abs() < 0.001, "{celsius} != 22.5");
+        assert!((percent - 41.27).abs() < 0.01, "{percent} != 41.27");
+
+        let restored = recompensate(
+            &recompensated,
+            temperature_from_celsius(2.5),
+            temperature_from_celsius(4.0),
+        );
+
+        let celsius = celsius_from_temperature(restored.temperature);
+        let percent = number_from_humidity(restored.humidity);
+
+        assert!((celsius - 21.0).abs() < 0.001, "{celsius} != 21");
+        assert!((percent - 45.0).abs() < 0.001, "{percent} != 45");
+    }
+}
@@ -7,34 +7,56 @@
 // https://opensource.org/licenses/Apache-2.0
 
 //! Data types and functions for checksum computation
+//!
+//! The sensor protects every word with a CRC-8 checksum, using polynomial
+//! `0x31` and initial value `0xff`.
+//! Three equivalent implementations are available:
+//!
+//! *   [`compute()`] processes one bit at a time, and has the smallest size.
+//! *   [`compute_with_table()`] uses a lookup table, and has the highest
+//!     throughput.
+//! *   [`compute_const()`] can be evaluated at compile time, e.g. for building
+//!     command frames as constants.
+//!
+//! ~~~~
+//! use scd4x_rs::checksum::compute_const;
+//!
+//! const FRAME: [u8; 5] = [0x24, 0x16, 0x00, 0x01, compute_const(&[0x00, 0x01])];
+//!
+//! assert_eq!(FRAME, [0x24, 0x16, 0x00, 0x01, 0xb0]);
+//! ~~~~
 
-use crate::Error;
+use crate::ProtocolError;
+
+/// Polynomial for CRC computation
+const CRC8_POLYNOMIAL: u8 = 0x31;
+
+/// Initial value for CRC computation
+const CRC8_INIT: u8 = 0xff;
+
+/// Lookup table for CRC computation
+const CRC8_TABLE: [u8; 256] = build_table();
 
 /// Verify that a buffer has a given checksum
 ///
 /// # Errors
 ///
 /// Returns an error if the checksum does not match.
-pub fn verify(data: [u8; 2], expected: u8) -> Result<(), Error> {
+pub fn verify(data: &[u8], expected: u8) -> Result<(), ProtocolError> {
     let actual = compute(data);
     if actual == expected {
         Ok(())
     } else {
-        Err(Error::ChecksumMismatch { actual, expected })
+        Err(ProtocolError::ChecksumMismatch { actual, expected })
     }
 }
 
-/// Compute the checksum of a buffer
-pub fn compute(data: [u8; 2]) -> u8 {
-    /// Polynomial for CRC computation
-    const CRC8_POLYNOMIAL: u8 = 0x31;
-
-    /// Initial value for CRC computation
-    const CRC8_INIT: u8 = 0xff;
-
+/// Compute the checksum of a buffer one bit at a time
+#[must_use]
+pub fn compute(data: &[u8]) -> u8 {
     let mut crc = CRC8_INIT;
 
-    for datum in data {
+    for &datum in data {
         crc ^= datum;
 
         #[expect(clippy::assign_op_pattern, reason = "Consistency within all branches")]
@@ -50,6 +72,58 @@
     crc
 }
 
+/// Compute the checksum of a buffer using a lookup table
+#[must_use]
+pub fn compute_with_table(data: &[u8]) -> u8 {
+    let mut crc = CRC8_INIT;
+
+    for &datum in data {
+        crc = CRC8_TABLE[usize::from(crc ^ datum)];
+    }
+
+    crc
+}
+
+/// Compute the checksum of a buffer at compile time
+#[must_use]
+pub const fn compute_const(data: &[u8]) -> u8 {
+    let mut crc = CRC8_INIT;
+
+    let mut index = 0;
+    while index < data.len() {
+        crc = CRC8_TABLE[(crc ^ data[index]) as usize];
+        index += 1;
+    }
+
+    crc
+}
+
+/// Build the lookup table for CRC computation
+const fn build_table() -> [u8; 256] {
+    let mut table = [0; 256];
+
+    let mut index = 0;
+    while index < table.len() {
+        #[expect(clippy::cast_possible_truncation, reason = "Index is within limits")]
+        let mut crc = index as u8;
+
+        let mut bit = 0_i32;
+        while bit < 8_i32 {
+            if crc & 0x80 != 0x00 {
+                crc = (crc << 1_i32) ^ CRC8_POLYNOMIAL;
+            } else {
+                crc <<= 1_i32;
+            }
+            bit += 1_i32;
+        }
+
+        table[index] = crc;
+        index += 1;
+    }
+
+    table
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -69,11 +143,73 @@ fn test_compute_checksum() {
 
         for &(input, expected) in samples {
             let buffer = input.to_be_bytes();
-            let actual = compute(buffer);
+            let actual = compute(&buffer);
             assert_eq!(
                 actual, expected,
                 "0x{input:04x} => 0x{actual:02x} == 0x{expected:02x}"
             );
+
+            let actual = compute_with_table(&buffer);
+            assert_eq!(
+                actual, expected,
+                "0x{input:04x} => 0x{actual:02x} == 0x{expected:02x}"
+            );
+
+            let actual = compute_const(&buffer);
+            assert_eq!(
+                actual, expected,
+                "0x{input:04x} => 0x{actual:02x} == 0x{expected:02x}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_compute_checksum_all_words() {
+        for input in 0..=u16::MAX {
+            let buffer = input.to_be_bytes();
+            let expected = compute(&buffer);
+
+            assert_eq!(compute_with_table(&buffer), expected, "0x{input:04x}");
+            assert_eq!(compute_const(&buffer), expected, "0x{input:04x}");
         }
     }
+
+    #[test]
+    fn test_compute_checksum_arbitrary_length() {
+        let data: [u8; 9] = [0x01, 0xf4, 0x33, 0x66, 0x67, 0xa2, 0x5e, 0xb9, 0x3c];
+
+        for length in 0..=data.len() {
+            let buffer = &data[..length];
+            let expected = compute(buffer);
+
+            assert_eq!(compute_with_table(buffer), expected, "length {length}");
+            assert_eq!(compute_const(buffer), expected, "length {length}");
+        }
+    }
+
+    #[test]
+    fn test_compute_checksum_empty() {
+        assert_eq!(compute(&[]), 0xff);
+        assert_eq!(compute_with_table(&[]), 0xff);
+        assert_eq!(compute_const(&[]), 0xff);
+    }
+
+    #[test]
+    fn test_compute_checksum_const() {
+        const CHECKSUM: u8 = compute_const(&[0xbe, 0xef]);
+
+        assert_eq!(CHECKSUM, 0x92);
+    }
+
+    #[test]
+    fn test_verify_checksum() {
+        assert_eq!(verify(&[0xbe, 0xef], 0x92), Ok(()));
+        assert_eq!(
+            verify(&[0xbe, 0xef], 0x93),
+            Err(ProtocolError::ChecksumMismatch {
+                actual: 0x92,
+                expected: 0x93
+            })
+        );
+    }
 }
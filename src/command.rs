@@ -0,0 +1,278 @@
+// Copyright Claudio Mattera 2024-2026.
+//
+// Distributed under the MIT License or the Apache 2.0 License at your option.
+// See the accompanying files LICENSE-MIT.txt and LICENSE-APACHE-2.0.txt, or
+// online at
+// https://opensource.org/licenses/MIT
+// https://opensource.org/licenses/Apache-2.0
+
+//! Data types and functions for SCD4x command types
+//!
+//! Commands and sequences are defined here independently of the I²C bus.
+//! Every sequence knows how to encode its request and decode its response, and
+//! the public [`Command`](crate::protocol::Command) trait is derived from them.
+//!
+//! The traits in this module are public because they appear in the bounds of
+//! the public trait, but they cannot be named outside of this crate.
+
+use core::time::Duration;
+
+use crate::compute_checksum;
+#[cfg(any(feature = "async", feature = "blocking"))]
+use crate::pending::Response;
+use crate::util::buffer_to_three_words;
+use crate::util::buffer_to_three_words_lenient;
+use crate::util::buffer_to_word;
+use crate::ProtocolError;
+
+/// A command to the sensor
+pub trait Command {
+    /// The sequence type of the command
+    type SequenceType: SequenceType<Input = Self::SequenceInput, Output = Self::SequenceOutput>;
+
+    /// The input type for the sequence type
+    type SequenceInput;
+
+    /// The output type for the sequence type
+    type SequenceOutput;
+
+    /// The input type for the command
+    type Input;
+
+    /// The output type for the command
+    type Output;
+
+    /// Return the I²C register of the command
+    fn register(&self) -> u16;
+
+    /// Return the maximal duration of the command
+    fn max_duration(&self) -> Duration;
+
+    /// Map the input for the command to the input for the sequence
+    fn preprocess(&self, input: Self::Input) -> Self::SequenceInput;
+
+    /// Map the output for the sequence to the output for the command
+    fn postprocess(&self, output: Self::SequenceOutput) -> Self::Output;
+}
+
+/// A command that can be sent without waiting for its completion
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub trait PendingCommand: Command {
+    /// Return the expected response from the sensor
+    fn response(&self) -> Response<Self::Output>;
+}
+
+/// An I²C sequence
+pub trait SequenceType {
+    /// The input type for the sequence
+    type Input;
+
+    /// The output type for the sequence
+    type Output;
+
+    /// The buffer written to the sensor
+    type Request: AsRef<[u8]>;
+
+    /// The buffer read from the sensor
+    type Response: AsRef<[u8]> + AsMut<[u8]> + Default;
+
+    /// Encode the register and the input to a buffer
+    fn encode(register: u16, input: Self::Input) -> Self::Request;
+
+    /// Decode a buffer to the output
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a checksum does not match.
+    fn decode(response: Self::Response) -> Result<Self::Output, ProtocolError>;
+}
+
+/// Encode a register and a word to a buffer
+fn encode_register_and_word(register: u16, input: u16) -> [u8; 5] {
+    let register_buffer: [u8; 2] = register.to_be_bytes();
+
+    let input_buffer = input.to_be_bytes();
+
+    let checksum = compute_checksum(&input_buffer);
+
+    [
+        register_buffer[0],
+        register_buffer[1],
+        input_buffer[0],
+        input_buffer[1],
+        checksum,
+    ]
+}
+
+/// A sequence for sending standalone commands
+pub struct SendCommandSequence;
+
+impl SequenceType for SendCommandSequence {
+    type Input = ();
+
+    type Output = ();
+
+    type Request = [u8; 2];
+
+    type Response = [u8; 0];
+
+    fn encode(register: u16, (): Self::Input) -> Self::Request {
+        register.to_be_bytes()
+    }
+
+    fn decode(_response: Self::Response) -> Result<Self::Output, ProtocolError> {
+        Ok(())
+    }
+}
+
+/// A sequence for sending commands and fetching result
+pub struct SendCommandAndFetchResultSequence;
+
+impl SequenceType for SendCommandAndFetchResultSequence {
+    type Input = u16;
+
+    type Output = u16;
+
+    type Request = [u8; 5];
+
+    type Response = [u8; 3];
+
+    fn encode(register: u16, input: Self::Input) -> Self::Request {
+        encode_register_and_word(register, input)
+    }
+
+    fn decode(response: Self::Response) -> Result<Self::Output, ProtocolError> {
+        buffer_to_word(response[0], response[1], response[2])
+    }
+}
+
+/// A sequence for reading a single word
+pub struct ReadWordSequence;
+
+impl SequenceType for ReadWordSequence {
+    type Input = ();
+
+    type Output = u16;
+
+    type Request = [u8; 2];
+
+    type Response = [u8; 3];
+
+    fn encode(register: u16, (): Self::Input) -> Self::Request {
+        register.to_be_bytes()
+    }
+
+    fn decode(response: Self::Response) -> Result<Self::Output, ProtocolError> {
+        buffer_to_word(response[0], response[1], response[2])
+    }
+}
+
+/// A sequence for reading three words
+pub struct ReadThreeWordsSequence;
+
+impl SequenceType for ReadThreeWordsSequence {
+    type Input = ();
+
+    type Output = (u16, u16, u16);
+
+    type Request = [u8; 2];
+
+    type Response = [u8; 9];
+
+    fn encode(register: u16, (): Self::Input) -> Self::Request {
+        register.to_be_bytes()
+    }
+
+    fn decode(response: Self::Response) -> Result<Self::Output, ProtocolError> {
+        buffer_to_three_words(response)
+    }
+}
+
+/// A sequence for reading three words, validating each word independently
+pub struct ReadThreeWordsLenientSequence;
+
+impl SequenceType for ReadThreeWordsLenientSequence {
+    type Input = ();
+
+    type Output = (Option<u16>, Option<u16>, Option<u16>);
+
+    type Request = [u8; 2];
+
+    type Response = [u8; 9];
+
+    fn encode(register: u16, (): Self::Input) -> Self::Request {
+        register.to_be_bytes()
+    }
+
+    fn decode(response: Self::Response) -> Result<Self::Output, ProtocolError> {
+        Ok(buffer_to_three_words_lenient(response))
+    }
+}
+
+/// A sequence for writing a word
+pub struct WriteWordSequence;
+
+impl SequenceType for WriteWordSequence {
+    type Input = u16;
+
+    type Output = ();
+
+    type Request = [u8; 5];
+
+    type Response = [u8; 0];
+
+    fn encode(register: u16, input: Self::Input) -> Self::Request {
+        encode_register_and_word(register, input)
+    }
+
+    fn decode(_response: Self::Response) -> Result<Self::Output, ProtocolError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![expect(clippy::panic_in_result_fn, reason = "Assertions in tests")]
+
+    use super::*;
+
+    #[test]
+    fn test_encode_register_and_word() {
+        let actual = WriteWordSequence::encode(0x241d, 0x07e6);
+        let expected = [0x24, 0x1d, 0x07, 0xe6, 0x48];
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_decode_three_words() -> Result<(), ProtocolError> {
+        let response = [0xf8, 0x96, 0x31, 0x9f, 0x07, 0xc2, 0x3b, 0xbe, 0x89];
+        let actual = ReadThreeWordsSequence::decode(response)?;
+        let expected = (0xf896, 0x9f07, 0x3bbe);
+
+        assert_eq!(actual, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_three_words_lenient() -> Result<(), ProtocolError> {
+        let response = [0xf8, 0x96, 0x31, 0x9f, 0x07, 0xc3, 0x3b, 0xbe, 0x89];
+        let actual = ReadThreeWordsLenientSequence::decode(response)?;
+        let expected = (Some(0xf896), None, Some(0x3bbe));
+
+        assert_eq!(actual, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_checksum_mismatch() {
+        let response = [0x01, 0xf4, 0x34];
+        let actual = ReadWordSequence::decode(response);
+        let expected = Err(ProtocolError::ChecksumMismatch {
+            actual: 0x33,
+            expected: 0x34,
+        });
+
+        assert_eq!(actual, expected);
+    }
+}
@@ -19,33 +19,41 @@
 
 use core::time::Duration;
 
+use crate::command::Command;
+#[cfg(any(feature = "async", feature = "blocking"))]
+use crate::command::PendingCommand;
+use crate::command::ReadThreeWordsLenientSequence;
+use crate::command::ReadThreeWordsSequence;
+use crate::command::ReadWordSequence;
+use crate::command::SendCommandAndFetchResultSequence;
+use crate::command::SendCommandSequence;
+use crate::command::WriteWordSequence;
 use crate::conversion::altitude_to_word;
 use crate::conversion::ambient_pressure_to_word;
 use crate::conversion::co2_to_word;
 use crate::conversion::signed_word_to_co2;
 use crate::conversion::temperature_offset_to_word;
 use crate::conversion::word_to_altitude;
+use crate::conversion::word_to_ambient_pressure;
 use crate::conversion::word_to_temperature_offset;
+use crate::conversion::words_to_partial_sample;
 use crate::conversion::words_to_sample;
 use crate::conversion::words_to_serial_number;
+#[cfg(any(feature = "async", feature = "blocking"))]
+use crate::pending::Response;
+use crate::sample::PartialSample;
+use crate::sample::RawSample;
 use crate::sample::Sample;
 use crate::Altitude;
 use crate::Co2;
 use crate::Pressure;
 use crate::Temperature;
 
-use super::command::Command;
-use super::command::ReadThreeWordsSequence;
-use super::command::ReadWordSequence;
-use super::command::SendCommandAndFetchResultSequence;
-use super::command::SendCommandSequence;
-use super::command::WriteWordSequence;
-
 /// Command for reinitializing the sensor
 ///
 /// The command restores user settings from EEPROM
 /// If reinitialization does not succeed, the sensor should be power-cycled.
-pub(crate) struct Reinitialize;
+pub struct Reinitialize;
 
 impl Command for Reinitialize {
     type SequenceType = SendCommandSequence;
@@ -63,7 +71,7 @@ fn register(&self) -> u16 {
     }
 
     fn max_duration(&self) -> Duration {
-        Duration::from_millis(20)
+        Duration::from_millis(30)
     }
 
     fn preprocess(&self, input: Self::Input) -> Self::SequenceInput {
@@ -75,11 +83,18 @@ fn postprocess(&self, output: Self::SequenceOutput) -> Self::Output {
     }
 }
 
+#[cfg(any(feature = "async", feature = "blocking"))]
+impl PendingCommand for Reinitialize {
+    fn response(&self) -> Response<Self::Output> {
+        Response::Empty(())
+    }
+}
+
 /// Command for getting the serial number
 ///
 /// The serial number is a 48 bits unique number that can identify the chip and
 /// verify the communication with the sensor.
-pub(crate) struct GetSerialNumber;
+pub struct GetSerialNumber;
 impl Command for GetSerialNumber {
     type SequenceType = ReadThreeWordsSequence;
 
@@ -114,7 +129,7 @@ fn postprocess(&self, (word0, word1, word2): Self::SequenceOutput) -> Self::Outp
 /// otherwise the sensor will return an I²C NACK.
 /// The command [`GetDataReadyStatus`] can be used to verify that data is
 /// available before executing this command.
-pub(crate) struct ReadMeasurement;
+pub struct ReadMeasurement;
 impl Command for ReadMeasurement {
     type SequenceType = ReadThreeWordsSequence;
 
@@ -143,10 +158,81 @@ fn postprocess(&self, (word0, word1, word2): Self::SequenceOutput) -> Self::Outp
     }
 }
 
+/// Command for reading a raw measurement from the sensor
+///
+/// This command is identical to [`ReadMeasurement`], but the words are returned
+/// without any conversion.
+pub struct ReadMeasurementRaw;
+impl Command for ReadMeasurementRaw {
+    type SequenceType = ReadThreeWordsSequence;
+
+    type Input = ();
+
+    type Output = RawSample;
+
+    type SequenceInput = ();
+
+    type SequenceOutput = (u16, u16, u16);
+
+    fn register(&self) -> u16 {
+        0xec05
+    }
+
+    fn max_duration(&self) -> Duration {
+        Duration::from_millis(1)
+    }
+
+    fn preprocess(&self, input: Self::Input) -> Self::SequenceInput {
+        input
+    }
+
+    fn postprocess(&self, (co2, temperature, humidity): Self::SequenceOutput) -> Self::Output {
+        RawSample {
+            co2,
+            temperature,
+            humidity,
+        }
+    }
+}
+
+/// Command for reading a measurement from the sensor, validating each field
+/// independently
+///
+/// This command is identical to [`ReadMeasurement`], but a checksum mismatch
+/// only invalidates the affected field instead of the whole sample.
+pub struct ReadMeasurementLenient;
+impl Command for ReadMeasurementLenient {
+    type SequenceType = ReadThreeWordsLenientSequence;
+
+    type Input = ();
+
+    type Output = PartialSample;
+
+    type SequenceInput = ();
+
+    type SequenceOutput = (Option<u16>, Option<u16>, Option<u16>);
+
+    fn register(&self) -> u16 {
+        0xec05
+    }
+
+    fn max_duration(&self) -> Duration {
+        Duration::from_millis(1)
+    }
+
+    fn preprocess(&self, input: Self::Input) -> Self::SequenceInput {
+        input
+    }
+
+    fn postprocess(&self, (word0, word1, word2): Self::SequenceOutput) -> Self::Output {
+        words_to_partial_sample(word0, word1, word2)
+    }
+}
+
 /// Command for starting periodic measurement
 ///
 /// The signal-update interval is 5 seconds.
-pub(crate) struct StartPeriodicMeasurement;
+pub struct StartPeriodicMeasurement;
 impl Command for StartPeriodicMeasurement {
     type SequenceType = SendCommandSequence;
 
@@ -176,7 +262,7 @@ fn postprocess(&self, output: Self::SequenceOutput) -> Self::Output {
 }
 
 /// Command for stopping periodic measurement
-pub(crate) struct StopPeriodicMeasurement;
+pub struct StopPeriodicMeasurement;
 impl Command for StopPeriodicMeasurement {
     type SequenceType = SendCommandSequence;
 
@@ -205,8 +291,15 @@ fn postprocess(&self, output: Self::SequenceOutput) -> Self::Output {
     }
 }
 
+#[cfg(any(feature = "async", feature = "blocking"))]
+impl PendingCommand for StopPeriodicMeasurement {
+    fn response(&self) -> Response<Self::Output> {
+        Response::Empty(())
+    }
+}
+
 /// Command for getting the temperature offset
-pub(crate) struct GetTemperatureOffset;
+pub struct GetTemperatureOffset;
 impl Command for GetTemperatureOffset {
     type SequenceType = ReadWordSequence;
 
@@ -236,7 +329,7 @@ fn postprocess(&self, word: Self::SequenceOutput) -> Self::Output {
 }
 
 /// Command for setting the temperature offset
-pub(crate) struct SetTemperatureOffset;
+pub struct SetTemperatureOffset;
 impl Command for SetTemperatureOffset {
     type SequenceType = WriteWordSequence;
 
@@ -266,7 +359,7 @@ fn postprocess(&self, output: Self::SequenceOutput) -> Self::Output {
 }
 
 /// Command for getting the sensor altitude
-pub(crate) struct GetSensorAltitude;
+pub struct GetSensorAltitude;
 impl Command for GetSensorAltitude {
     type SequenceType = ReadWordSequence;
 
@@ -296,7 +389,7 @@ fn postprocess(&self, word: Self::SequenceOutput) -> Self::Output {
 }
 
 /// Command for setting the sensor altitude
-pub(crate) struct SetSensorAltitude;
+pub struct SetSensorAltitude;
 impl Command for SetSensorAltitude {
     type SequenceType = WriteWordSequence;
 
@@ -325,8 +418,38 @@ fn postprocess(&self, output: Self::SequenceOutput) -> Self::Output {
     }
 }
 
+/// Command for getting the ambient pressure
+pub struct GetAmbientPressure;
+impl Command for GetAmbientPressure {
+    type SequenceType = ReadWordSequence;
+
+    type Input = ();
+
+    type Output = Pressure;
+
+    type SequenceInput = ();
+
+    type SequenceOutput = u16;
+
+    fn register(&self) -> u16 {
+        0xe000
+    }
+
+    fn max_duration(&self) -> Duration {
+        Duration::from_millis(1)
+    }
+
+    fn preprocess(&self, input: Self::Input) -> Self::SequenceInput {
+        input
+    }
+
+    fn postprocess(&self, word: Self::SequenceOutput) -> Self::Output {
+        word_to_ambient_pressure(word)
+    }
+}
+
 /// Command for setting the ambient pressure
-pub(crate) struct SetAmbientPressure;
+pub struct SetAmbientPressure;
 impl Command for SetAmbientPressure {
     type SequenceType = WriteWordSequence;
 
@@ -356,7 +479,7 @@ fn postprocess(&self, output: Self::SequenceOutput) -> Self::Output {
 }
 
 /// Command for starting low-power periodic measurement
-pub(crate) struct StartLowPowerPeriodicMeasurement;
+pub struct StartLowPowerPeriodicMeasurement;
 impl Command for StartLowPowerPeriodicMeasurement {
     type SequenceType = SendCommandSequence;
 
@@ -386,7 +509,7 @@ fn postprocess(&self, output: Self::SequenceOutput) -> Self::Output {
 }
 
 /// Command for querying whether data is available to be read
-pub(crate) struct GetDataReadyStatus;
+pub struct GetDataReadyStatus;
 impl Command for GetDataReadyStatus {
     type SequenceType = ReadWordSequence;
 
@@ -417,7 +540,7 @@ fn postprocess(&self, word: Self::SequenceOutput) -> Self::Output {
 }
 
 /// Command for performing a self-test
-pub(crate) struct PerformSelfTest;
+pub struct PerformSelfTest;
 impl Command for PerformSelfTest {
     type SequenceType = ReadWordSequence;
 
@@ -434,7 +557,7 @@ fn register(&self) -> u16 {
     }
 
     fn max_duration(&self) -> Duration {
-        Duration::from_millis(10000)
+        Duration::from_secs(10)
     }
 
     fn preprocess(&self, input: Self::Input) -> Self::SequenceInput {
@@ -446,8 +569,15 @@ fn postprocess(&self, word: Self::SequenceOutput) -> Self::Output {
     }
 }
 
+#[cfg(any(feature = "async", feature = "blocking"))]
+impl PendingCommand for PerformSelfTest {
+    fn response(&self) -> Response<Self::Output> {
+        Response::Word(|word| Self.postprocess(word))
+    }
+}
+
 /// Command for reading a single-shot measurement
-pub(crate) struct MeasureSingleShot;
+pub struct MeasureSingleShot;
 impl Command for MeasureSingleShot {
     type SequenceType = SendCommandSequence;
 
@@ -464,7 +594,7 @@ fn register(&self) -> u16 {
     }
 
     fn max_duration(&self) -> Duration {
-        Duration::from_millis(5000)
+        Duration::from_secs(5)
     }
 
     fn preprocess(&self, input: Self::Input) -> Self::SequenceInput {
@@ -476,8 +606,15 @@ fn postprocess(&self, input: Self::SequenceOutput) -> Self::Output {
     }
 }
 
+#[cfg(any(feature = "async", feature = "blocking"))]
+impl PendingCommand for MeasureSingleShot {
+    fn response(&self) -> Response<Self::Output> {
+        Response::Empty(())
+    }
+}
+
 /// Command for reading a single-shot measurement of humidity and temperature
-pub(crate) struct MeasureSingleShotRhtOnly;
+pub struct MeasureSingleShotRhtOnly;
 impl Command for MeasureSingleShotRhtOnly {
     type SequenceType = SendCommandSequence;
 
@@ -506,8 +643,15 @@ fn postprocess(&self, input: Self::SequenceOutput) -> Self::Output {
     }
 }
 
+#[cfg(any(feature = "async", feature = "blocking"))]
+impl PendingCommand for MeasureSingleShotRhtOnly {
+    fn response(&self) -> Response<Self::Output> {
+        Response::Empty(())
+    }
+}
+
 /// Command for persisting settings
-pub(crate) struct PersistSettings;
+pub struct PersistSettings;
 impl Command for PersistSettings {
     type SequenceType = SendCommandSequence;
 
@@ -536,8 +680,15 @@ fn postprocess(&self, input: Self::SequenceOutput) -> Self::Output {
     }
 }
 
+#[cfg(any(feature = "async", feature = "blocking"))]
+impl PendingCommand for PersistSettings {
+    fn response(&self) -> Response<Self::Output> {
+        Response::Empty(())
+    }
+}
+
 /// Command for performing forced recalibration
-pub(crate) struct PerformForcedRecalibration;
+pub struct PerformForcedRecalibration;
 impl Command for PerformForcedRecalibration {
     type SequenceType = SendCommandAndFetchResultSequence;
 
@@ -575,8 +726,15 @@ fn postprocess(&self, word: Self::SequenceOutput) -> Self::Output {
     }
 }
 
+#[cfg(any(feature = "async", feature = "blocking"))]
+impl PendingCommand for PerformForcedRecalibration {
+    fn response(&self) -> Response<Self::Output> {
+        Response::Word(|word| Self.postprocess(word))
+    }
+}
+
 /// Command for querying whether automatic self-calibration is enabled
-pub(crate) struct GetAutomaticSelfCalibrationEnabled;
+pub struct GetAutomaticSelfCalibrationEnabled;
 impl Command for GetAutomaticSelfCalibrationEnabled {
     type SequenceType = ReadWordSequence;
 
@@ -606,7 +764,7 @@ fn postprocess(&self, word: Self::SequenceOutput) -> Self::Output {
 }
 
 /// Command for setting whether automatic self-calibration is enabled
-pub(crate) struct SetAutomaticSelfCalibrationEnabled;
+pub struct SetAutomaticSelfCalibrationEnabled;
 impl Command for SetAutomaticSelfCalibrationEnabled {
     type SequenceType = WriteWordSequence;
 
@@ -636,7 +794,7 @@ fn postprocess(&self, input: Self::SequenceOutput) -> Self::Output {
 }
 
 /// Command for performing factory reset
-pub(crate) struct PerformFactoryReset;
+pub struct PerformFactoryReset;
 impl Command for PerformFactoryReset {
     type SequenceType = SendCommandSequence;
 
@@ -664,3 +822,10 @@ fn postprocess(&self, input: Self::SequenceOutput) -> Self::Output {
         input
     }
 }
+
+#[cfg(any(feature = "async", feature = "blocking"))]
+impl PendingCommand for PerformFactoryReset {
+    fn response(&self) -> Response<Self::Output> {
+        Response::Empty(())
+    }
+}
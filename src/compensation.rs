@@ -0,0 +1,254 @@
+// Copyright Claudio Mattera 2024-2026.
+//
+// Distributed under the MIT License or the Apache 2.0 License at your option.
+// See the accompanying files LICENSE-MIT.txt and LICENSE-APACHE-2.0.txt, or
+// online at
+// https://opensource.org/licenses/MIT
+// https://opensource.org/licenses/Apache-2.0
+
+//! Macro for ambient pressure compensation from an external barometer
+//!
+//! The blocking and the asynchronous compensators only differ in the HAL crate
+//! and in the `async` and `.await` keywords, so both are generated from the
+//! same source, together with their tests.
+
+use libm::fabsf;
+
+use crate::sample::hectopascal_from_pressure;
+use crate::Pressure;
+
+/// Return whether the sensor should be updated with a new pressure
+///
+/// The sensor is updated if it was never updated before, or if the pressure
+/// changed by more than the delta since the last update.
+pub(crate) fn needs_update(last: Option<Pressure>, pressure: Pressure, delta: Pressure) -> bool {
+    match last {
+        Some(last) => {
+            let change = hectopascal_from_pressure(pressure) - hectopascal_from_pressure(last);
+            fabsf(change) > hectopascal_from_pressure(delta)
+        }
+        None => true,
+    }
+}
+
+/// Define the pressure source trait, the compensator and their tests
+///
+/// The macro must be invoked either as `define_compensation!(blocking)` or as
+/// `define_compensation!(async)`, in a module next to a module `sensor`
+/// defined with the corresponding invocation of
+/// [`define_sensor`](crate::sensor::define_sensor).
+macro_rules! define_compensation {
+    (blocking) => {
+        $crate::compensation::define_compensation!(@impl embedded_hal, [], [], [test]);
+    };
+    (async) => {
+        $crate::compensation::define_compensation!(@impl embedded_hal_async, [async], [.await], [tokio::test]);
+    };
+    (@impl $hal:ident, [$($async:tt)*], [$($await:tt)*], [$($test:tt)*]) => {
+        use $hal::delay::DelayNs;
+        use $hal::i2c::I2c;
+
+        use $crate::compensation::needs_update;
+        use $crate::macros::debug;
+        use $crate::CompensationError;
+        use $crate::Pressure;
+        use $crate::State;
+
+        use super::sensor::Scd4x;
+
+        /// A source of ambient pressure, e.g. an external barometer
+        #[allow(
+            async_fn_in_trait,
+            reason = "Same convention as the traits of embedded-hal-async"
+        )]
+        pub trait PressureSource {
+            /// The error type of the source
+            type Error;
+
+            /// Read the current ambient pressure
+            ///
+            /// # Errors
+            ///
+            /// Return an error if the pressure cannot be read.
+            $($async)* fn read_pressure(&mut self) -> Result<Pressure, Self::Error>;
+        }
+
+        /// A helper for compensating the sensor for ambient pressure
+        ///
+        /// The compensator reads the ambient pressure from a source, and sets it
+        /// on the sensor only when it changed by more than a delta since the last
+        /// time, avoiding unnecessary traffic on the I²C bus.
+        #[derive(Debug)]
+        pub struct PressureCompensator<P> {
+            /// Source of ambient pressure
+            source: P,
+
+            /// Smallest change in pressure that is sent to the sensor
+            delta: Pressure,
+
+            /// Last pressure sent to the sensor
+            last: Option<Pressure>,
+        }
+
+        impl<P> PressureCompensator<P>
+        where
+            P: PressureSource,
+        {
+            /// Create a new compensator from a source and a delta
+            pub fn new(source: P, delta: Pressure) -> Self {
+                Self {
+                    source,
+                    delta,
+                    last: None,
+                }
+            }
+
+            /// Release the source of ambient pressure
+            pub fn release(self) -> P {
+                self.source
+            }
+
+            /// Return the last pressure sent to the sensor
+            pub fn last_pressure(&self) -> Option<Pressure> {
+                self.last
+            }
+
+            /// Read the ambient pressure, and set it on the sensor if needed
+            ///
+            /// This function should be called before each measurement cycle.
+            /// Return whether the pressure was set on the sensor.
+            ///
+            /// # Errors
+            ///
+            /// Return an error if the pressure cannot be read, or if it cannot
+            /// communicate with the sensor.
+            pub $($async)* fn compensate<I2C, D, S>(
+                &mut self,
+                sensor: &mut Scd4x<I2C, D, S>,
+            ) -> Result<bool, CompensationError<P::Error>>
+            where
+                I2C: I2c,
+                D: DelayNs,
+                S: State,
+            {
+                let pressure = self
+                    .source
+                    .read_pressure()
+                    $($await)*
+                    .map_err(CompensationError::Source)?;
+
+                if !needs_update(self.last, pressure, self.delta) {
+                    debug!("Ambient pressure did not change enough");
+                    return Ok(false);
+                }
+
+                sensor.set_ambient_pressure(pressure)$($await)*?;
+                self.last = Some(pressure);
+
+                Ok(true)
+            }
+
+            /// Forget the last pressure sent to the sensor
+            ///
+            /// The next call to [`PressureCompensator::compensate()`] will set the
+            /// pressure on the sensor unconditionally.
+            pub fn reset(&mut self) {
+                self.last = None;
+            }
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use embedded_hal_mock::eh1::delay::NoopDelay as DelayMock;
+            use embedded_hal_mock::eh1::i2c::Mock as I2cMock;
+            use embedded_hal_mock::eh1::i2c::Transaction as I2cTransaction;
+
+            use super::*;
+
+            use $crate::constants::DEFAULT_ADDRESS;
+            use $crate::sample::pressure_from_hectopascal;
+            use $crate::Error;
+
+            /// A pressure source returning scripted values
+            struct ScriptedSource {
+                /// Values to return, in hectoPascal
+                values: core::slice::Iter<'static, f32>,
+            }
+
+            impl PressureSource for ScriptedSource {
+                type Error = ();
+
+                $($async)* fn read_pressure(&mut self) -> Result<Pressure, Self::Error> {
+                    self.values
+                        .next()
+                        .map(|&hectopascal| pressure_from_hectopascal(hectopascal))
+                        .ok_or(())
+                }
+            }
+
+            #[$($test)*]
+            $($async)* fn test_compensate() -> Result<(), CompensationError<()>> {
+                let expectations = [
+                    I2cTransaction::write(DEFAULT_ADDRESS, vec![0xe0, 0x00, 0x03, 0xdb, 0x42]),
+                    I2cTransaction::write(DEFAULT_ADDRESS, vec![0xe0, 0x00, 0x03, 0xd4, 0x6c]),
+                ];
+                let i2c = I2cMock::new(&expectations);
+
+                let mut scd4x = Scd4x::new_in_measuring(i2c, DelayMock);
+
+                let source = ScriptedSource {
+                    values: [987.0, 987.5, 986.2, 980.0, 980.9].iter(),
+                };
+                let mut compensator =
+                    PressureCompensator::new(source, pressure_from_hectopascal(1.0));
+
+                assert!(compensator.compensate(&mut scd4x)$($await)*?);
+                assert!(!compensator.compensate(&mut scd4x)$($await)*?);
+                assert!(!compensator.compensate(&mut scd4x)$($await)*?);
+                assert!(compensator.compensate(&mut scd4x)$($await)*?);
+                assert!(!compensator.compensate(&mut scd4x)$($await)*?);
+
+                assert_eq!(
+                    compensator.last_pressure(),
+                    Some(pressure_from_hectopascal(980.0))
+                );
+
+                let result = compensator.compensate(&mut scd4x)$($await)*;
+                assert_eq!(result, Err(CompensationError::Source(())));
+
+                scd4x.release().done();
+                Ok(())
+            }
+
+            #[$($test)*]
+            $($async)* fn test_compensate_sensor_error() {
+                let expectations =
+                    [
+                        I2cTransaction::write(DEFAULT_ADDRESS, vec![0xe0, 0x00, 0x03, 0xdb, 0x42])
+                            .with_error(embedded_hal::i2c::ErrorKind::Other),
+                    ];
+                let i2c = I2cMock::new(&expectations);
+
+                let mut scd4x = Scd4x::new_in_measuring(i2c, DelayMock);
+
+                let source = ScriptedSource {
+                    values: [987.0].iter(),
+                };
+                let mut compensator =
+                    PressureCompensator::new(source, pressure_from_hectopascal(1.0));
+
+                let result = compensator.compensate(&mut scd4x)$($await)*;
+                let expected = Err(CompensationError::Sensor(Error::I2c(
+                    embedded_hal::i2c::ErrorKind::Other,
+                )));
+
+                assert_eq!(result, expected);
+                assert_eq!(compensator.last_pressure(), None);
+
+                scd4x.release().done();
+            }
+        }
+    };
+}
+
+pub(crate) use define_compensation;
@@ -8,14 +8,21 @@
 
 //! Data types and functions for conversions between values and words
 
+#[cfg(feature = "sim")]
+use libm::roundf;
+
 use crate::sample::altitude_from_meter;
 use crate::sample::celsius_from_temperature;
 use crate::sample::co2_from_ppm;
 use crate::sample::hectopascal_from_pressure;
 use crate::sample::humidity_from_number;
 use crate::sample::meter_from_altitude;
+#[cfg(feature = "sim")]
+use crate::sample::number_from_humidity;
 use crate::sample::ppm_from_co2;
+use crate::sample::pressure_from_hectopascal;
 use crate::sample::temperature_from_celsius;
+use crate::sample::PartialSample;
 use crate::sample::Sample;
 use crate::Altitude;
 use crate::Co2;
@@ -41,10 +48,53 @@ pub(crate) fn words_to_sample(word0: u16, word1: u16, word2: u16) -> Sample {
     }
 }
 
+/// Convert three optional words to a partial sample
+pub(crate) fn words_to_partial_sample(
+    word0: Option<u16>,
+    word1: Option<u16>,
+    word2: Option<u16>,
+) -> PartialSample {
+    PartialSample {
+        co2: word0.map(|word| co2_from_ppm(f32::from(word))),
+        temperature: word1.map(word_to_temperature),
+        humidity: word2.map(word_to_humidity),
+    }
+}
+
 /// Convert a word to a humidity value
 pub(crate) fn word_to_humidity(word: u16) -> Humidity {
-    let humidity = (100_f32 * f32::from(word)) / 65536_f32;
-    humidity_from_number(humidity)
+    humidity_from_number(word_to_percent(word))
+}
+
+/// Convert a word to a humidity in percent
+fn word_to_percent(word: u16) -> f32 {
+    (100_f32 * f32::from(word)) / 65536_f32
+}
+
+#[cfg(feature = "fixed-point")]
+/// Convert a word to a humidity in thousandths of percent
+///
+/// The result is equal to [`word_to_percent()`] multiplied by 1000 and rounded
+/// half away from zero.
+pub(crate) fn word_to_millipercent(word: u16) -> u32 {
+    // 100 000 * word / 65 536 = 3 125 * word / 2 048
+    let numerator = 3_125_u32 * u32::from(word);
+    (numerator + 1_024_u32) / 2_048_u32
+}
+
+#[cfg(feature = "sim")]
+/// Convert a humidity value to a word
+pub(crate) fn humidity_to_word(humidity: Humidity) -> u16 {
+    let intermediate = (65536_f32 * number_from_humidity(humidity)) / 100_f32;
+
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        reason = "Manually validated"
+    )]
+    let word = roundf(intermediate).clamp(0_f32, f32::from(u16::MAX)) as u16;
+
+    word
 }
 
 /// Convert a word to an altitude value
@@ -91,8 +141,48 @@ pub(crate) fn temperature_offset_to_word(temperature_offset: Temperature) -> u16
 
 /// Convert a word to a temperature value
 pub(crate) fn word_to_temperature(word: u16) -> Temperature {
-    let celsius = -45_f32 + (175_f32 * f32::from(word)) / 65536_f32;
-    temperature_from_celsius(celsius)
+    temperature_from_celsius(word_to_celsius(word))
+}
+
+/// Convert a word to a temperature in Celsius
+fn word_to_celsius(word: u16) -> f32 {
+    -45_f32 + (175_f32 * f32::from(word)) / 65536_f32
+}
+
+#[cfg(feature = "sim")]
+/// Convert a temperature value to a word
+pub(crate) fn temperature_to_word(temperature: Temperature) -> u16 {
+    let intermediate = (65536_f32 * (celsius_from_temperature(temperature) + 45_f32)) / 175_f32;
+
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        reason = "Manually validated"
+    )]
+    let word = roundf(intermediate).clamp(0_f32, f32::from(u16::MAX)) as u16;
+
+    word
+}
+
+#[cfg(feature = "fixed-point")]
+/// Convert a word to a temperature in thousandths of Celsius
+///
+/// The result is equal to [`word_to_celsius()`] multiplied by 1000 and rounded
+/// half away from zero.
+pub(crate) fn word_to_millicelsius(word: u16) -> i32 {
+    // -45 000 + 175 000 * word / 65 536 = (21 875 * word - 368 640 000) / 8 192
+    let numerator = 21_875_i32 * i32::from(word) - 368_640_000_i32;
+    if numerator >= 0_i32 {
+        (numerator + 4_096_i32) / 8_192_i32
+    } else {
+        -((-numerator + 4_096_i32) / 8_192_i32)
+    }
+}
+
+/// Convert a word to an ambient pressure value
+pub(crate) fn word_to_ambient_pressure(word: u16) -> Pressure {
+    let hectopascals = f32::from(word);
+    pressure_from_hectopascal(hectopascals)
 }
 
 /// Convert an ambient pressure value to a word
@@ -131,6 +221,11 @@ pub(crate) fn co2_to_word(co2: Co2) -> u16 {
 
 #[cfg(test)]
 mod tests {
+    #![cfg_attr(
+        not(feature = "uom"),
+        expect(clippy::float_cmp, reason = "Exact values are expected")
+    )]
+
     use super::*;
 
     #[test]
@@ -155,6 +250,24 @@ fn test_words_to_reading() {
         assert_eq!(actual, expected);
     }
 
+    #[cfg(feature = "sim")]
+    #[test]
+    fn test_temperature_to_word() {
+        for word in [0x0000, 0x6667, 0xffff] {
+            let actual = temperature_to_word(word_to_temperature(word));
+            assert_eq!(actual, word);
+        }
+    }
+
+    #[cfg(feature = "sim")]
+    #[test]
+    fn test_humidity_to_word() {
+        for word in [0x0000, 0x5eb9, 0xffff] {
+            let actual = humidity_to_word(word_to_humidity(word));
+            assert_eq!(actual, word);
+        }
+    }
+
     #[test]
     fn test_word_to_temperature_offset() {
         let word = 0x0912;
@@ -172,4 +285,28 @@ fn test_temperature_offset_to_word() {
 
         assert_eq!(actual, expected);
     }
+
+    #[cfg(feature = "fixed-point")]
+    #[test]
+    #[expect(clippy::float_cmp, reason = "Rounded values are exact")]
+    fn test_word_to_millicelsius() {
+        for word in 0..=u16::MAX {
+            let actual = word_to_millicelsius(word);
+            let expected = (f64::from(word_to_celsius(word)) * 1000.0).round();
+
+            assert_eq!(f64::from(actual), expected, "word {word:#06x}");
+        }
+    }
+
+    #[cfg(feature = "fixed-point")]
+    #[test]
+    #[expect(clippy::float_cmp, reason = "Rounded values are exact")]
+    fn test_word_to_millipercent() {
+        for word in 0..=u16::MAX {
+            let actual = word_to_millipercent(word);
+            let expected = (f64::from(word_to_percent(word)) * 1000.0).round();
+
+            assert_eq!(f64::from(actual), expected, "word {word:#06x}");
+        }
+    }
 }
@@ -0,0 +1,231 @@
+// Copyright Claudio Mattera 2024-2026.
+//
+// Distributed under the MIT License or the Apache 2.0 License at your option.
+// See the accompanying files LICENSE-MIT.txt and LICENSE-APACHE-2.0.txt, or
+// online at
+// https://opensource.org/licenses/MIT
+// https://opensource.org/licenses/Apache-2.0
+
+//! Execution times from the [SCD4x Datasheet], version 1.6, for testing
+//!
+//! Values are transcribed from the command overview of the datasheet, in the
+//! same order and with the same names, and they are not derived from the
+//! command definitions.
+//! Commands without an execution time in the datasheet have a zero execution
+//! time.
+//! Commands that are not implemented by this crate are omitted.
+//!
+//! The datasheet specifies the same execution times for SCD40, SCD41 and
+//! SCD43.
+//! Variants only differ in the commands they support: single-shot
+//! measurements are not available on SCD40.
+//!
+//! [SCD4x Datasheet]: https://sensirion.com/products/catalog/SCD40/
+
+use core::time::Duration;
+
+/// A variant of the sensor
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Variant {
+    /// SCD40
+    Scd40,
+
+    /// SCD41
+    Scd41,
+
+    /// SCD43
+    Scd43,
+}
+
+impl Variant {
+    /// All variants
+    pub(crate) const ALL: [Self; 3] = [Self::Scd40, Self::Scd41, Self::Scd43];
+}
+
+/// A command in the datasheet
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Timing {
+    /// Name of the command in the datasheet
+    pub(crate) name: &'static str,
+
+    /// Register of the command
+    pub(crate) register: u16,
+
+    /// Execution time of the command
+    pub(crate) execution_time: Duration,
+
+    /// Whether the command is available on SCD40
+    pub(crate) scd40: bool,
+}
+
+impl Timing {
+    /// Create a command available on all variants
+    const fn all(name: &'static str, register: u16, milliseconds: u64) -> Self {
+        Self {
+            name,
+            register,
+            execution_time: Duration::from_millis(milliseconds),
+            scd40: true,
+        }
+    }
+
+    /// Create a command available on SCD41 and SCD43 only
+    const fn scd41_scd43(name: &'static str, register: u16, milliseconds: u64) -> Self {
+        Self {
+            name,
+            register,
+            execution_time: Duration::from_millis(milliseconds),
+            scd40: false,
+        }
+    }
+
+    /// Return whether the command is available on a variant
+    pub(crate) fn is_available_on(&self, variant: Variant) -> bool {
+        match variant {
+            Variant::Scd40 => self.scd40,
+            Variant::Scd41 | Variant::Scd43 => true,
+        }
+    }
+}
+
+/// Commands in the datasheet
+pub(crate) const TIMINGS: [Timing; 21] = [
+    // Basic commands
+    Timing::all("start_periodic_measurement", 0x21b1, 0),
+    Timing::all("read_measurement", 0xec05, 1),
+    Timing::all("stop_periodic_measurement", 0x3f86, 500),
+    // On-chip output signal compensation
+    Timing::all("set_temperature_offset", 0x241d, 1),
+    Timing::all("get_temperature_offset", 0x2318, 1),
+    Timing::all("set_sensor_altitude", 0x2427, 1),
+    Timing::all("get_sensor_altitude", 0x2322, 1),
+    Timing::all("set_ambient_pressure", 0xe000, 1),
+    Timing::all("get_ambient_pressure", 0xe000, 1),
+    // Field calibration
+    Timing::all("perform_forced_recalibration", 0x362f, 400),
+    Timing::all("set_automatic_self_calibration_enabled", 0x2416, 1),
+    Timing::all("get_automatic_self_calibration_enabled", 0x2313, 1),
+    // Low power periodic measurement mode
+    Timing::all("start_low_power_periodic_measurement", 0x21ac, 0),
+    Timing::all("get_data_ready_status", 0xe4b8, 1),
+    // Advanced features
+    Timing::all("persist_settings", 0x3615, 800),
+    Timing::all("get_serial_number", 0x3682, 1),
+    Timing::all("perform_self_test", 0x3639, 10_000),
+    Timing::all("perform_factory_reset", 0x3632, 1_200),
+    Timing::all("reinit", 0x3646, 30),
+    // Single shot measurement mode
+    Timing::scd41_scd43("measure_single_shot", 0x219d, 5_000),
+    Timing::scd41_scd43("measure_single_shot_rht_only", 0x2196, 50),
+];
+
+/// Return the longest execution time of the commands with a register
+///
+/// Return `None` if the register is unknown.
+pub(crate) fn execution_time(register: u16) -> Option<Duration> {
+    TIMINGS
+        .iter()
+        .filter(|timing| timing.register == register)
+        .map(|timing| timing.execution_time)
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::command::Command;
+    use crate::commands::*;
+
+    /// Name, register and maximal duration of all commands
+    fn commands() -> [(&'static str, u16, Duration); 23] {
+        /// Return name, register and maximal duration of a command
+        macro_rules! entry {
+            ($command:ident) => {
+                (
+                    stringify!($command),
+                    $command.register(),
+                    $command.max_duration(),
+                )
+            };
+        }
+
+        [
+            entry!(Reinitialize),
+            entry!(GetSerialNumber),
+            entry!(ReadMeasurement),
+            entry!(ReadMeasurementRaw),
+            entry!(ReadMeasurementLenient),
+            entry!(StartPeriodicMeasurement),
+            entry!(StopPeriodicMeasurement),
+            entry!(GetTemperatureOffset),
+            entry!(SetTemperatureOffset),
+            entry!(GetSensorAltitude),
+            entry!(SetSensorAltitude),
+            entry!(GetAmbientPressure),
+            entry!(SetAmbientPressure),
+            entry!(StartLowPowerPeriodicMeasurement),
+            entry!(GetDataReadyStatus),
+            entry!(PerformSelfTest),
+            entry!(MeasureSingleShot),
+            entry!(MeasureSingleShotRhtOnly),
+            entry!(PersistSettings),
+            entry!(PerformForcedRecalibration),
+            entry!(GetAutomaticSelfCalibrationEnabled),
+            entry!(SetAutomaticSelfCalibrationEnabled),
+            entry!(PerformFactoryReset),
+        ]
+    }
+
+    #[test]
+    fn test_max_durations() {
+        for (name, register, max_duration) in commands() {
+            let expected = execution_time(register);
+            assert!(expected.is_some(), "{name}: missing from datasheet");
+
+            if let Some(expected) = expected {
+                assert!(
+                    max_duration >= expected,
+                    "{name}: {max_duration:?} < {expected:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_all_timings_are_implemented() {
+        for timing in TIMINGS {
+            let implemented = commands()
+                .iter()
+                .any(|&(_, register, _)| register == timing.register);
+            assert!(implemented, "{}: not implemented", timing.name);
+        }
+    }
+
+    #[test]
+    fn test_variants() {
+        let availability = |name| {
+            TIMINGS
+                .iter()
+                .find(|timing| timing.name == name)
+                .map(|timing| Variant::ALL.map(|variant| timing.is_available_on(variant)))
+        };
+
+        assert_eq!(
+            availability("measure_single_shot"),
+            Some([false, true, true])
+        );
+        assert_eq!(
+            availability("measure_single_shot_rht_only"),
+            Some([false, true, true])
+        );
+        assert_eq!(availability("perform_self_test"), Some([true, true, true]));
+    }
+
+    #[test]
+    fn test_execution_time() {
+        assert_eq!(execution_time(0x3639), Some(Duration::from_secs(10)));
+        assert_eq!(execution_time(0xe000), Some(Duration::from_millis(1)));
+        assert_eq!(execution_time(0x0000), None);
+    }
+}
@@ -8,10 +8,37 @@
 
 //! Data types and functions for error handling
 
+#[cfg(any(feature = "async", feature = "blocking", feature = "sim"))]
 use embedded_hal::i2c::Error as I2cError;
+#[cfg(any(feature = "async", feature = "blocking", feature = "sim"))]
 use embedded_hal::i2c::ErrorKind as I2cErrorKind;
 
+/// An error while decoding a response from the sensor
+///
+/// This error is independent of the I²C implementation, and is returned by
+/// the sans-I/O [`protocol`](crate::protocol) layer.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ProtocolError {
+    /// A checksum was different than expected
+    ChecksumMismatch {
+        /// Actual checksum
+        actual: u8,
+
+        /// Expected checksum
+        expected: u8,
+    },
+}
+
+impl core::error::Error for ProtocolError {}
+
+impl core::fmt::Display for ProtocolError {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(formatter, "{self:?}")
+    }
+}
+
 /// An error
+#[cfg(any(feature = "async", feature = "blocking", feature = "sim"))]
 #[derive(Debug, PartialEq)]
 pub enum Error {
     /// A checksum was different than expected
@@ -27,6 +54,18 @@ pub enum Error {
     I2c(I2cErrorKind),
 }
 
+#[cfg(any(feature = "async", feature = "blocking", feature = "sim"))]
+impl From<ProtocolError> for Error {
+    fn from(error: ProtocolError) -> Self {
+        match error {
+            ProtocolError::ChecksumMismatch { actual, expected } => {
+                Self::ChecksumMismatch { actual, expected }
+            }
+        }
+    }
+}
+
+#[cfg(any(feature = "async", feature = "blocking", feature = "sim"))]
 impl<E> From<E> for Error
 where
     E: I2cError,
@@ -36,10 +75,43 @@ fn from(error: E) -> Self {
     }
 }
 
+#[cfg(any(feature = "async", feature = "blocking", feature = "sim"))]
 impl core::error::Error for Error {}
 
+#[cfg(any(feature = "async", feature = "blocking", feature = "sim"))]
 impl core::fmt::Display for Error {
     fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
         write!(formatter, "{self:?}")
     }
 }
+
+/// An error while compensating the sensor for ambient pressure
+#[cfg(any(feature = "async", feature = "blocking"))]
+#[derive(Debug, PartialEq)]
+pub enum CompensationError<E> {
+    /// An error in the source of ambient pressure
+    Source(E),
+
+    /// An error in the sensor
+    Sensor(Error),
+}
+
+#[cfg(any(feature = "async", feature = "blocking"))]
+impl<E> From<Error> for CompensationError<E> {
+    fn from(error: Error) -> Self {
+        Self::Sensor(error)
+    }
+}
+
+#[cfg(any(feature = "async", feature = "blocking"))]
+impl<E> core::error::Error for CompensationError<E> where E: core::fmt::Debug {}
+
+#[cfg(any(feature = "async", feature = "blocking"))]
+impl<E> core::fmt::Display for CompensationError<E>
+where
+    E: core::fmt::Debug,
+{
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(formatter, "{self:?}")
+    }
+}
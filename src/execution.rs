@@ -0,0 +1,144 @@
+// Copyright Claudio Mattera 2024-2026.
+//
+// Distributed under the MIT License or the Apache 2.0 License at your option.
+// See the accompanying files LICENSE-MIT.txt and LICENSE-APACHE-2.0.txt, or
+// online at
+// https://opensource.org/licenses/MIT
+// https://opensource.org/licenses/Apache-2.0
+
+//! Macro for executing SCD4x commands over an I²C bus
+//!
+//! Commands are encoded and decoded by the sans-I/O [`protocol`](crate::protocol)
+//! module, so executing them only requires writing and reading buffers.
+//! The blocking and the asynchronous implementations only differ in the HAL
+//! crate and in the `async` and `.await` keywords, so both are generated from
+//! the same source.
+
+/// Define the traits and functions for executing commands over an I²C bus
+///
+/// The macro must be invoked either as `define_execution!(blocking)` or as
+/// `define_execution!(async)`.
+macro_rules! define_execution {
+    (blocking) => {
+        $crate::execution::define_execution!(@impl embedded_hal, [], []);
+    };
+    (async) => {
+        $crate::execution::define_execution!(@impl embedded_hal_async, [async], [.await]);
+    };
+    (@impl $hal:ident, [$($async:tt)*], [$($await:tt)*]) => {
+        use $hal::delay::DelayNs;
+        use $hal::i2c::I2c;
+
+        use $crate::command::PendingCommand;
+        use $crate::macros::trace;
+        use $crate::pending::Response;
+        use $crate::protocol::Command;
+        use $crate::util::buffer_to_word;
+        use $crate::Error;
+        use $crate::Pending;
+
+        /// Execution of a command over the I²C bus
+        pub(crate) trait ExecuteCommand: Command {
+            /// Execute the command over the I²C bus
+            $($async)* fn execute(
+                &self,
+                address: u8,
+                i2c: &mut impl I2c,
+                delayer: &mut impl DelayNs,
+                input: Self::Input,
+            ) -> Result<Self::Output, Error>;
+
+            /// Send the command over the I²C bus without waiting for its completion
+            $($async)* fn begin(
+                &self,
+                address: u8,
+                i2c: &mut impl I2c,
+                input: <Self as Command>::Input,
+            ) -> Result<Pending<<Self as Command>::Output>, Error>
+            where
+                Self: PendingCommand<Output = <Self as Command>::Output>;
+        }
+
+        impl<C> ExecuteCommand for C
+        where
+            C: Command,
+        {
+            $($async)* fn execute(
+                &self,
+                address: u8,
+                i2c: &mut impl I2c,
+                delayer: &mut impl DelayNs,
+                input: Self::Input,
+            ) -> Result<Self::Output, Error> {
+                let request = self.encode(input);
+                write(address, i2c, request.as_ref())$($await)*?;
+
+                let delay = self.execution_time();
+                trace!("Wait {:?}", delay);
+                #[expect(
+                    clippy::cast_possible_truncation,
+                    reason = "Constants are within limits"
+                )]
+                delayer.delay_ms(delay.as_millis() as u32)$($await)*;
+
+                let mut response = C::Response::default();
+                read(address, i2c, response.as_mut())$($await)*?;
+                self.decode(response).map_err(Error::from)
+            }
+
+            $($async)* fn begin(
+                &self,
+                address: u8,
+                i2c: &mut impl I2c,
+                input: <Self as Command>::Input,
+            ) -> Result<Pending<<Self as Command>::Output>, Error>
+            where
+                Self: PendingCommand<Output = <Self as Command>::Output>,
+            {
+                let request = self.encode(input);
+                write(address, i2c, request.as_ref())$($await)*?;
+                Ok(Pending::new(self.execution_time(), self.response()))
+            }
+        }
+
+        /// Read the result of a pending command over the I²C bus
+        pub(crate) $($async)* fn finish<T>(
+            address: u8,
+            i2c: &mut impl I2c,
+            pending: Pending<T>,
+        ) -> Result<T, Error> {
+            match pending.into_response() {
+                Response::Empty(output) => Ok(output),
+                Response::Word(decode) => {
+                    let mut buffer: [u8; 3] = [0; 3];
+                    read(address, i2c, &mut buffer)$($await)*?;
+                    let word = buffer_to_word(buffer[0], buffer[1], buffer[2])?;
+                    Ok(decode(word))
+                }
+            }
+        }
+
+        /// Write a buffer over the I²C bus
+        $($async)* fn write(address: u8, i2c: &mut impl I2c, buffer: &[u8]) -> Result<(), Error> {
+            trace!("Write data {:?}", buffer);
+            i2c.write(address, buffer)$($await)*?;
+
+            Ok(())
+        }
+
+        /// Read a buffer over the I²C bus, unless it is empty
+        $($async)* fn read(address: u8, i2c: &mut impl I2c, buffer: &mut [u8]) -> Result<(), Error> {
+            if buffer.is_empty() {
+                return Ok(());
+            }
+
+            trace!("Read data");
+            i2c.read(address, buffer)$($await)*?;
+            trace!("Read data {:?}", buffer);
+
+            Ok(())
+        }
+    };
+}
+
+pub(crate) use define_execution;
@@ -0,0 +1,384 @@
+// Copyright Claudio Mattera 2024-2026.
+//
+// Distributed under the MIT License or the Apache 2.0 License at your option.
+// See the accompanying files LICENSE-MIT.txt and LICENSE-APACHE-2.0.txt, or
+// online at
+// https://opensource.org/licenses/MIT
+// https://opensource.org/licenses/Apache-2.0
+
+//! Data types and functions for filtering streams of samples
+//!
+//! A [`SampleFilter`] smooths each field of a [`Sample`] independently.
+//! It does not allocate, and it keeps at most `N` samples in a fixed-size
+//! buffer.
+//!
+//! ~~~~
+//! use scd4x_rs::filter::SampleFilter;
+//! use scd4x_rs::Sample;
+//! # #[cfg(not(feature = "uom"))]
+//! # {
+//!
+//! let mut filter = SampleFilter::<3>::median();
+//!
+//! for co2 in [600.0, 610.0, 2000.0, 620.0] {
+//!     let sample = Sample { co2, temperature: 21.0, humidity: 40.0 };
+//!     let filtered = filter.update(sample);
+//!     assert!(filtered.co2 < 1000.0);
+//! }
+//! # }
+//! ~~~~
+
+use crate::sample::celsius_from_temperature;
+use crate::sample::co2_from_ppm;
+use crate::sample::humidity_from_number;
+use crate::sample::number_from_humidity;
+use crate::sample::ppm_from_co2;
+use crate::sample::temperature_from_celsius;
+use crate::Sample;
+
+/// Number of fields in a sample
+const FIELDS: usize = 3;
+
+/// An error while creating a filter
+#[derive(Debug, PartialEq, Eq)]
+pub enum FilterError {
+    /// A smoothing factor is not in the range `(0, 1]`
+    InvalidSmoothingFactor,
+}
+
+impl core::error::Error for FilterError {}
+
+impl core::fmt::Display for FilterError {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(formatter, "{self:?}")
+    }
+}
+
+/// A smoothing factor for exponential smoothing, in the range `(0, 1]`
+///
+/// Larger values give more weight to recent samples.
+/// A factor of 1 returns the samples unchanged.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SmoothingFactor(f32);
+
+impl SmoothingFactor {
+    /// Create a new smoothing factor
+    ///
+    /// # Errors
+    ///
+    /// Return an error if the factor is not in the range `(0, 1]`, including
+    /// if it is NaN.
+    pub fn new(factor: f32) -> Result<Self, FilterError> {
+        if factor > 0.0 && factor <= 1.0 {
+            Ok(Self(factor))
+        } else {
+            Err(FilterError::InvalidSmoothingFactor)
+        }
+    }
+
+    /// Return the smoothing factor as a number
+    #[must_use]
+    pub fn get(self) -> f32 {
+        self.0
+    }
+}
+
+/// Filtering algorithm
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum FilterMode {
+    /// Simple moving average of the last `N` samples
+    MovingAverage,
+
+    /// Exponential smoothing with the given smoothing factor
+    ExponentialSmoothing(SmoothingFactor),
+
+    /// Median of the last `N` samples
+    ///
+    /// The median rejects isolated spikes, which would otherwise skew an
+    /// average.
+    Median,
+}
+
+/// A filter for streams of samples
+///
+/// The filter keeps the last `N` samples, and each field is filtered
+/// independently.
+/// If `N` is zero, moving average and median return the samples unchanged.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SampleFilter<const N: usize> {
+    /// Filtering algorithm
+    mode: FilterMode,
+
+    /// Last samples as raw values, in a circular buffer
+    buffer: [[f32; FIELDS]; N],
+
+    /// Number of samples in the buffer
+    length: usize,
+
+    /// Position of the next sample in the buffer
+    next: usize,
+
+    /// Last output of exponential smoothing
+    smoothed: Option<[f32; FIELDS]>,
+}
+
+impl<const N: usize> SampleFilter<N> {
+    /// Create a new filter
+    #[must_use]
+    pub fn new(mode: FilterMode) -> Self {
+        Self {
+            mode,
+            buffer: [[0.0; FIELDS]; N],
+            length: 0,
+            next: 0,
+            smoothed: None,
+        }
+    }
+
+    /// Create a new simple moving-average filter
+    #[must_use]
+    pub fn moving_average() -> Self {
+        Self::new(FilterMode::MovingAverage)
+    }
+
+    /// Create a new exponential-smoothing filter
+    ///
+    /// # Errors
+    ///
+    /// Return an error if the smoothing factor is not in the range `(0, 1]`.
+    pub fn exponential_smoothing(factor: f32) -> Result<Self, FilterError> {
+        let factor = SmoothingFactor::new(factor)?;
+        Ok(Self::new(FilterMode::ExponentialSmoothing(factor)))
+    }
+
+    /// Create a new median filter
+    #[must_use]
+    pub fn median() -> Self {
+        Self::new(FilterMode::Median)
+    }
+
+    /// Return the filtering algorithm
+    #[must_use]
+    pub fn mode(&self) -> FilterMode {
+        self.mode
+    }
+
+    /// Return the number of samples kept by the filter
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Return whether the filter does not keep any sample
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Add a sample to the filter, and return the filtered sample
+    pub fn update(&mut self, sample: Sample) -> Sample {
+        let values = sample_to_values(&sample);
+
+        if N > 0 {
+            self.buffer[self.next] = values;
+            self.next = (self.next + 1) % N;
+            self.length = (self.length + 1).min(N);
+        }
+
+        let filtered = match self.mode {
+            FilterMode::MovingAverage if N > 0 => self.average(),
+            FilterMode::Median if N > 0 => self.median_values(),
+            FilterMode::MovingAverage | FilterMode::Median => values,
+            FilterMode::ExponentialSmoothing(factor) => {
+                let factor = factor.get();
+                let smoothed = match self.smoothed {
+                    Some(previous) => {
+                        core::array::from_fn(|i| factor * values[i] + (1.0 - factor) * previous[i])
+                    }
+                    None => values,
+                };
+                self.smoothed = Some(smoothed);
+                smoothed
+            }
+        };
+
+        values_to_sample(filtered)
+    }
+
+    /// Forget all samples
+    pub fn reset(&mut self) {
+        self.length = 0;
+        self.next = 0;
+        self.smoothed = None;
+    }
+
+    /// Compute the average of each field of the samples in the buffer
+    fn average(&self) -> [f32; FIELDS] {
+        let mut sums = [0.0; FIELDS];
+        for values in &self.buffer[..self.length] {
+            for (sum, value) in sums.iter_mut().zip(values) {
+                *sum += value;
+            }
+        }
+
+        #[expect(
+            clippy::cast_precision_loss,
+            reason = "Buffer length is small enough to be represented exactly"
+        )]
+        let length = self.length as f32;
+
+        sums.map(|sum| sum / length)
+    }
+
+    /// Compute the median of each field of the samples in the buffer
+    fn median_values(&self) -> [f32; FIELDS] {
+        core::array::from_fn(|field| {
+            let mut column = [0.0; N];
+            for (value, values) in column.iter_mut().zip(&self.buffer[..self.length]) {
+                *value = values[field];
+            }
+
+            let column = &mut column[..self.length];
+            column.sort_unstable_by(f32::total_cmp);
+
+            let middle = self.length / 2;
+            if self.length % 2 == 0 {
+                (column[middle - 1] + column[middle]) / 2.0
+            } else {
+                column[middle]
+            }
+        })
+    }
+}
+
+/// Convert a sample to raw values
+fn sample_to_values(sample: &Sample) -> [f32; FIELDS] {
+    [
+        ppm_from_co2(sample.co2),
+        celsius_from_temperature(sample.temperature),
+        number_from_humidity(sample.humidity),
+    ]
+}
+
+/// Convert raw values to a sample
+fn values_to_sample([co2, temperature, humidity]: [f32; FIELDS]) -> Sample {
+    Sample {
+        co2: co2_from_ppm(co2),
+        temperature: temperature_from_celsius(temperature),
+        humidity: humidity_from_number(humidity),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::sample::sample;
+
+    /// Assert that a sample is close to the expected raw values
+    fn assert_close(actual: &Sample, expected: [f32; FIELDS]) {
+        let actual = sample_to_values(actual);
+        for (actual, expected) in actual.iter().zip(expected) {
+            assert!((actual - expected).abs() < 1e-3, "{actual} != {expected}");
+        }
+    }
+
+    #[test]
+    fn test_moving_average() {
+        let mut filter = SampleFilter::<3>::moving_average();
+        assert!(filter.is_empty());
+
+        let filtered = filter.update(sample(600.0, 20.0, 40.0));
+        assert_close(&filtered, [600.0, 20.0, 40.0]);
+
+        let filtered = filter.update(sample(630.0, 21.0, 42.0));
+        assert_close(&filtered, [615.0, 20.5, 41.0]);
+
+        let filtered = filter.update(sample(660.0, 22.0, 44.0));
+        assert_close(&filtered, [630.0, 21.0, 42.0]);
+
+        let filtered = filter.update(sample(690.0, 23.0, 46.0));
+        assert_close(&filtered, [660.0, 22.0, 44.0]);
+
+        assert_eq!(filter.len(), 3);
+    }
+
+    #[test]
+    fn test_exponential_smoothing() -> Result<(), FilterError> {
+        let mut filter = SampleFilter::<0>::exponential_smoothing(0.25)?;
+
+        let filtered = filter.update(sample(600.0, 20.0, 40.0));
+        assert_close(&filtered, [600.0, 20.0, 40.0]);
+
+        let filtered = filter.update(sample(1000.0, 24.0, 48.0));
+        assert_close(&filtered, [700.0, 21.0, 42.0]);
+
+        let filtered = filter.update(sample(1000.0, 24.0, 48.0));
+        assert_close(&filtered, [775.0, 21.75, 43.5]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_exponential_smoothing_unit_factor() -> Result<(), FilterError> {
+        let mut filter = SampleFilter::<0>::exponential_smoothing(1.0)?;
+
+        let filtered = filter.update(sample(600.0, 20.0, 40.0));
+        assert_close(&filtered, [600.0, 20.0, 40.0]);
+
+        let filtered = filter.update(sample(1000.0, 24.0, 48.0));
+        assert_close(&filtered, [1000.0, 24.0, 48.0]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_smoothing_factors() {
+        for factor in [0.0, -0.25, 1.5, f32::NAN, f32::INFINITY] {
+            assert_eq!(
+                SampleFilter::<0>::exponential_smoothing(factor),
+                Err(FilterError::InvalidSmoothingFactor),
+                "factor {factor}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_median_rejects_spikes() {
+        let mut filter = SampleFilter::<3>::median();
+
+        let filtered = filter.update(sample(600.0, 20.0, 40.0));
+        assert_close(&filtered, [600.0, 20.0, 40.0]);
+
+        let filtered = filter.update(sample(610.0, 35.0, 41.0));
+        assert_close(&filtered, [605.0, 27.5, 40.5]);
+
+        let filtered = filter.update(sample(5000.0, 21.0, 42.0));
+        assert_close(&filtered, [610.0, 21.0, 41.0]);
+
+        let filtered = filter.update(sample(620.0, 22.0, 90.0));
+        assert_close(&filtered, [620.0, 22.0, 42.0]);
+    }
+
+    #[test]
+    fn test_zero_capacity() {
+        let mut filter = SampleFilter::<0>::median();
+
+        let filtered = filter.update(sample(600.0, 20.0, 40.0));
+        assert_close(&filtered, [600.0, 20.0, 40.0]);
+        assert!(filter.is_empty());
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut filter = SampleFilter::<4>::moving_average();
+
+        let _filtered = filter.update(sample(600.0, 20.0, 40.0));
+        let _filtered = filter.update(sample(800.0, 20.0, 40.0));
+        filter.reset();
+
+        let filtered = filter.update(sample(1000.0, 25.0, 50.0));
+        assert_close(&filtered, [1000.0, 25.0, 50.0]);
+        assert_eq!(filter.len(), 1);
+    }
+}
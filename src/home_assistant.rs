@@ -0,0 +1,549 @@
+// Copyright Claudio Mattera 2024-2026.
+//
+// Distributed under the MIT License or the Apache 2.0 License at your option.
+// See the accompanying files LICENSE-MIT.txt and LICENSE-APACHE-2.0.txt, or
+// online at
+// https://opensource.org/licenses/MIT
+// https://opensource.org/licenses/Apache-2.0
+
+//! Payloads for Home Assistant MQTT discovery
+//!
+//! [Home Assistant] discovers sensors from configuration payloads published
+//! to retained MQTT topics.
+//! A [`Discovery`] generates the configuration topics and payloads of three
+//! entities, [`Entity::Co2`], [`Entity::Temperature`] and
+//! [`Entity::Humidity`], all grouped under one device identified by the
+//! serial number of the sensor.
+//! All entities read their values from the same state topic, whose payload is
+//! generated from a [`Sample`].
+//!
+//! Topics and payloads are written into buffers provided by the caller, and
+//! no memory is allocated.
+//!
+//! ~~~~
+//! use scd4x_rs::home_assistant::Discovery;
+//! use scd4x_rs::home_assistant::Entity;
+//!
+//! let discovery = Discovery::new(0xf896_9f07_3bbe, "scd4x")?;
+//!
+//! let mut topic = [0; 128];
+//! let mut payload = [0; 512];
+//! for entity in Entity::ALL {
+//!     let topic = discovery.config_topic(entity, &mut topic)?;
+//!     let payload = discovery.config_payload(entity, &mut payload)?;
+//!     // Publish retained payload to topic
+//! }
+//!
+//! assert_eq!(
+//!     discovery.config_topic(Entity::Co2, &mut topic)?,
+//!     "homeassistant/sensor/scd4x_f8969f073bbe/co2/config",
+//! );
+//! assert_eq!(
+//!     discovery.state_topic(&mut topic)?,
+//!     "scd4x/f8969f073bbe/state",
+//! );
+//! # Ok::<(), scd4x_rs::home_assistant::DiscoveryError>(())
+//! ~~~~
+//!
+//! [Home Assistant]: https://www.home-assistant.io/integrations/mqtt/#mqtt-discovery
+
+use core::fmt::Error as FmtError;
+use core::fmt::Result as FmtResult;
+use core::fmt::Write;
+use core::str::from_utf8;
+
+use crate::sample::celsius_from_temperature;
+use crate::sample::number_from_humidity;
+use crate::sample::ppm_from_co2;
+use crate::telemetry::SerialNumber;
+use crate::Sample;
+
+/// Default prefix of discovery topics in Home Assistant
+pub const DEFAULT_DISCOVERY_PREFIX: &str = "homeassistant";
+
+/// An entity of the sensor
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Entity {
+    /// CO₂ concentration in ppm
+    Co2,
+
+    /// Temperature in °C
+    Temperature,
+
+    /// Relative humidity in %
+    Humidity,
+}
+
+impl Entity {
+    /// All entities
+    pub const ALL: [Self; 3] = [Self::Co2, Self::Temperature, Self::Humidity];
+
+    /// Return the identifier of the entity, also used as key in the state payload
+    #[must_use]
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::Co2 => "co2",
+            Self::Temperature => "temperature",
+            Self::Humidity => "humidity",
+        }
+    }
+
+    /// Return the name of the entity
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Co2 => "CO2",
+            Self::Temperature => "Temperature",
+            Self::Humidity => "Humidity",
+        }
+    }
+
+    /// Return the device class of the entity in Home Assistant
+    #[must_use]
+    pub fn device_class(self) -> &'static str {
+        match self {
+            Self::Co2 => "carbon_dioxide",
+            Self::Temperature => "temperature",
+            Self::Humidity => "humidity",
+        }
+    }
+
+    /// Return the unit of measurement of the entity in Home Assistant
+    #[must_use]
+    pub fn unit(self) -> &'static str {
+        match self {
+            Self::Co2 => "ppm",
+            Self::Temperature => "°C",
+            Self::Humidity => "%",
+        }
+    }
+
+    /// Return the number of decimals shown by Home Assistant
+    #[must_use]
+    pub fn display_precision(self) -> u8 {
+        match self {
+            Self::Co2 => 0,
+            Self::Temperature | Self::Humidity => 1,
+        }
+    }
+
+    /// Return the number of decimals in the state payload
+    fn state_decimals(self) -> usize {
+        match self {
+            Self::Co2 => 0,
+            Self::Temperature | Self::Humidity => 2,
+        }
+    }
+}
+
+/// An error while generating discovery topics and payloads
+#[derive(Debug, PartialEq, Eq)]
+pub enum DiscoveryError {
+    /// A topic prefix is empty, or contains wildcards, quotes, backslashes or
+    /// control characters
+    InvalidTopicPrefix,
+
+    /// A buffer is too small for the topic or payload
+    BufferTooSmall,
+}
+
+impl From<FmtError> for DiscoveryError {
+    fn from(_error: FmtError) -> Self {
+        Self::BufferTooSmall
+    }
+}
+
+impl core::error::Error for DiscoveryError {}
+
+impl core::fmt::Display for DiscoveryError {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(formatter, "{self:?}")
+    }
+}
+
+/// A generator of Home Assistant MQTT discovery topics and payloads
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Discovery<'prefix> {
+    /// Serial number of the sensor
+    serial_number: SerialNumber,
+
+    /// Prefix of the state topic
+    topic_prefix: &'prefix str,
+
+    /// Prefix of the discovery topics
+    config_prefix: &'prefix str,
+}
+
+impl<'prefix> Discovery<'prefix> {
+    /// Create a new generator with the default discovery prefix
+    /// [`DEFAULT_DISCOVERY_PREFIX`]
+    ///
+    /// The state topic is `<topic_prefix>/<serial_number>/state`.
+    ///
+    /// # Errors
+    ///
+    /// Return an error if the topic prefix is not valid.
+    pub fn new(serial_number: u64, topic_prefix: &'prefix str) -> Result<Self, DiscoveryError> {
+        Self::new_with_discovery_prefix(serial_number, topic_prefix, DEFAULT_DISCOVERY_PREFIX)
+    }
+
+    /// Create a new generator with a custom discovery prefix
+    ///
+    /// # Errors
+    ///
+    /// Return an error if either prefix is not valid.
+    pub fn new_with_discovery_prefix(
+        serial_number: u64,
+        topic_prefix: &'prefix str,
+        discovery_prefix: &'prefix str,
+    ) -> Result<Self, DiscoveryError> {
+        if !is_valid_prefix(topic_prefix) || !is_valid_prefix(discovery_prefix) {
+            return Err(DiscoveryError::InvalidTopicPrefix);
+        }
+
+        Ok(Self {
+            serial_number: SerialNumber(serial_number),
+            topic_prefix,
+            config_prefix: discovery_prefix,
+        })
+    }
+
+    /// Write the configuration topic of an entity
+    ///
+    /// The topic is `<discovery_prefix>/sensor/scd4x_<serial_number>/<entity>/config`.
+    ///
+    /// # Errors
+    ///
+    /// Return an error if the buffer is too small.
+    pub fn config_topic<'buf>(
+        &self,
+        entity: Entity,
+        buffer: &'buf mut [u8],
+    ) -> Result<&'buf str, DiscoveryError> {
+        write_into(buffer, |output| {
+            write!(
+                output,
+                "{}/sensor/scd4x_{}/{}/config",
+                self.config_prefix,
+                self.serial_number,
+                entity.id()
+            )
+        })
+    }
+
+    /// Write the configuration payload of an entity
+    ///
+    /// The payload should be published as retained message to the
+    /// configuration topic.
+    ///
+    /// # Errors
+    ///
+    /// Return an error if the buffer is too small.
+    pub fn config_payload<'buf>(
+        &self,
+        entity: Entity,
+        buffer: &'buf mut [u8],
+    ) -> Result<&'buf str, DiscoveryError> {
+        let serial_number = self.serial_number;
+        let id = entity.id();
+
+        write_into(buffer, |output| {
+            write!(
+                output,
+                "{{\"name\":\"{}\",\
+                 \"unique_id\":\"scd4x_{serial_number}_{id}\",\
+                 \"default_entity_id\":\"sensor.scd4x_{serial_number}_{id}\",\
+                 \"device_class\":\"{}\",\
+                 \"state_class\":\"measurement\",\
+                 \"unit_of_measurement\":\"{}\",\
+                 \"suggested_display_precision\":{},\
+                 \"state_topic\":\"",
+                entity.name(),
+                entity.device_class(),
+                entity.unit(),
+                entity.display_precision(),
+            )?;
+            self.write_state_topic(output)?;
+            write!(
+                output,
+                "\",\
+                 \"value_template\":\"{{{{ value_json.{id} }}}}\",\
+                 \"device\":{{\
+                 \"identifiers\":[\"scd4x_{serial_number}\"],\
+                 \"name\":\"SCD4x {serial_number}\",\
+                 \"manufacturer\":\"Sensirion\",\
+                 \"model\":\"SCD4x\",\
+                 \"serial_number\":\"{serial_number}\"}}}}"
+            )
+        })
+    }
+
+    /// Write the state topic shared by all entities
+    ///
+    /// # Errors
+    ///
+    /// Return an error if the buffer is too small.
+    pub fn state_topic<'buf>(&self, buffer: &'buf mut [u8]) -> Result<&'buf str, DiscoveryError> {
+        write_into(buffer, |output| self.write_state_topic(output))
+    }
+
+    /// Write the state payload of a sample
+    ///
+    /// Non-finite values are written as `null`.
+    ///
+    /// # Errors
+    ///
+    /// Return an error if the buffer is too small.
+    pub fn state_payload<'buf>(
+        &self,
+        sample: &Sample,
+        buffer: &'buf mut [u8],
+    ) -> Result<&'buf str, DiscoveryError> {
+        let values = [
+            (Entity::Co2, ppm_from_co2(sample.co2)),
+            (
+                Entity::Temperature,
+                celsius_from_temperature(sample.temperature),
+            ),
+            (Entity::Humidity, number_from_humidity(sample.humidity)),
+        ];
+
+        write_into(buffer, |output| {
+            let mut separator = '{';
+            for (entity, value) in values {
+                write!(output, "{separator}\"{}\":", entity.id())?;
+                if value.is_finite() {
+                    write!(
+                        output,
+                        "{value:.decimals$}",
+                        decimals = entity.state_decimals()
+                    )?;
+                } else {
+                    output.write_str("null")?;
+                }
+                separator = ',';
+            }
+            output.write_char('}')
+        })
+    }
+
+    /// Write the state topic
+    fn write_state_topic<W>(&self, output: &mut W) -> FmtResult
+    where
+        W: Write,
+    {
+        write!(output, "{}/{}/state", self.topic_prefix, self.serial_number)
+    }
+}
+
+/// Return whether a topic prefix is valid
+///
+/// A prefix must not be empty, and must not contain MQTT wildcards or
+/// characters that would need escaping in JSON strings.
+fn is_valid_prefix(prefix: &str) -> bool {
+    !prefix.is_empty()
+        && !prefix
+            .chars()
+            .any(|character| matches!(character, '+' | '#' | '"' | '\\') || character.is_control())
+}
+
+/// Write into a buffer, and return the written string
+fn write_into<F>(buffer: &mut [u8], write: F) -> Result<&str, DiscoveryError>
+where
+    F: FnOnce(&mut BufferWriter<'_>) -> FmtResult,
+{
+    let mut writer = BufferWriter { buffer, length: 0 };
+    write(&mut writer)?;
+    let BufferWriter { buffer, length } = writer;
+
+    let written = buffer.get(..length).ok_or(DiscoveryError::BufferTooSmall)?;
+    from_utf8(written).map_err(|_error| DiscoveryError::BufferTooSmall)
+}
+
+/// A writer into a fixed-size buffer
+struct BufferWriter<'buf> {
+    /// Underlying buffer
+    buffer: &'buf mut [u8],
+
+    /// Number of bytes written
+    length: usize,
+}
+
+impl Write for BufferWriter<'_> {
+    fn write_str(&mut self, string: &str) -> FmtResult {
+        let end = self.length.checked_add(string.len()).ok_or(FmtError)?;
+        let destination = self.buffer.get_mut(self.length..end).ok_or(FmtError)?;
+        destination.copy_from_slice(string.as_bytes());
+        self.length = end;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![expect(clippy::panic_in_result_fn, reason = "Assertions in tests")]
+
+    use super::*;
+
+    use crate::sample::co2_from_ppm;
+    use crate::sample::humidity_from_number;
+    use crate::sample::temperature_from_celsius;
+
+    /// Serial number used in tests
+    const SERIAL_NUMBER: u64 = 0xf896_9f07_3bbe;
+
+    #[test]
+    fn test_config_topics() -> Result<(), DiscoveryError> {
+        let discovery = Discovery::new(SERIAL_NUMBER, "scd4x")?;
+        let mut buffer = [0; 128];
+
+        let topics =
+            Entity::ALL.map(|entity| discovery.config_topic(entity, &mut buffer).map(str::len));
+        assert_eq!(topics, [Ok(50), Ok(58), Ok(55)]);
+
+        assert_eq!(
+            discovery.config_topic(Entity::Temperature, &mut buffer)?,
+            "homeassistant/sensor/scd4x_f8969f073bbe/temperature/config"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_payloads() -> Result<(), DiscoveryError> {
+        let discovery = Discovery::new_with_discovery_prefix(SERIAL_NUMBER, "home/air", "ha")?;
+        let mut buffer = [0; 512];
+
+        let device = "\"device\":{\
+             \"identifiers\":[\"scd4x_f8969f073bbe\"],\
+             \"name\":\"SCD4x f8969f073bbe\",\
+             \"manufacturer\":\"Sensirion\",\
+             \"model\":\"SCD4x\",\
+             \"serial_number\":\"f8969f073bbe\"}";
+
+        assert_eq!(
+            discovery.config_payload(Entity::Co2, &mut buffer)?,
+            format!(
+                "{{\"name\":\"CO2\",\
+                 \"unique_id\":\"scd4x_f8969f073bbe_co2\",\
+                 \"default_entity_id\":\"sensor.scd4x_f8969f073bbe_co2\",\
+                 \"device_class\":\"carbon_dioxide\",\
+                 \"state_class\":\"measurement\",\
+                 \"unit_of_measurement\":\"ppm\",\
+                 \"suggested_display_precision\":0,\
+                 \"state_topic\":\"home/air/f8969f073bbe/state\",\
+                 \"value_template\":\"{{{{ value_json.co2 }}}}\",\
+                 {device}}}"
+            )
+        );
+        assert_eq!(
+            discovery.config_payload(Entity::Temperature, &mut buffer)?,
+            format!(
+                "{{\"name\":\"Temperature\",\
+                 \"unique_id\":\"scd4x_f8969f073bbe_temperature\",\
+                 \"default_entity_id\":\"sensor.scd4x_f8969f073bbe_temperature\",\
+                 \"device_class\":\"temperature\",\
+                 \"state_class\":\"measurement\",\
+                 \"unit_of_measurement\":\"°C\",\
+                 \"suggested_display_precision\":1,\
+                 \"state_topic\":\"home/air/f8969f073bbe/state\",\
+                 \"value_template\":\"{{{{ value_json.temperature }}}}\",\
+                 {device}}}"
+            )
+        );
+        assert_eq!(
+            discovery.config_payload(Entity::Humidity, &mut buffer)?,
+            format!(
+                "{{\"name\":\"Humidity\",\
+                 \"unique_id\":\"scd4x_f8969f073bbe_humidity\",\
+                 \"default_entity_id\":\"sensor.scd4x_f8969f073bbe_humidity\",\
+                 \"device_class\":\"humidity\",\
+                 \"state_class\":\"measurement\",\
+                 \"unit_of_measurement\":\"%\",\
+                 \"suggested_display_precision\":1,\
+                 \"state_topic\":\"home/air/f8969f073bbe/state\",\
+                 \"value_template\":\"{{{{ value_json.humidity }}}}\",\
+                 {device}}}"
+            )
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_state() -> Result<(), DiscoveryError> {
+        let discovery = Discovery::new(SERIAL_NUMBER, "scd4x")?;
+        let mut buffer = [0; 64];
+
+        assert_eq!(
+            discovery.state_topic(&mut buffer)?,
+            "scd4x/f8969f073bbe/state"
+        );
+
+        let sample = Sample {
+            co2: co2_from_ppm(605.0),
+            temperature: temperature_from_celsius(21.5),
+            humidity: humidity_from_number(42.25),
+        };
+        assert_eq!(
+            discovery.state_payload(&sample, &mut buffer)?,
+            "{\"co2\":605,\"temperature\":21.50,\"humidity\":42.25}"
+        );
+
+        let sample = Sample {
+            co2: co2_from_ppm(f32::NAN),
+            temperature: temperature_from_celsius(-3.25),
+            humidity: humidity_from_number(f32::INFINITY),
+        };
+        assert_eq!(
+            discovery.state_payload(&sample, &mut buffer)?,
+            "{\"co2\":null,\"temperature\":-3.25,\"humidity\":null}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_buffer_too_small() -> Result<(), DiscoveryError> {
+        let discovery = Discovery::new(SERIAL_NUMBER, "scd4x")?;
+
+        let mut buffer = [0; 24];
+        assert_eq!(
+            discovery.state_topic(&mut buffer),
+            Ok("scd4x/f8969f073bbe/state")
+        );
+
+        let mut buffer = [0; 23];
+        assert_eq!(
+            discovery.state_topic(&mut buffer),
+            Err(DiscoveryError::BufferTooSmall)
+        );
+
+        let mut buffer = [0; 128];
+        assert_eq!(
+            discovery.config_payload(Entity::Co2, &mut buffer),
+            Err(DiscoveryError::BufferTooSmall)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_prefixes() {
+        for prefix in [
+            "",
+            "scd4x/#",
+            "scd4x/+/air",
+            "quote\"",
+            "back\\slash",
+            "new\nline",
+        ] {
+            assert_eq!(
+                Discovery::new(SERIAL_NUMBER, prefix),
+                Err(DiscoveryError::InvalidTopicPrefix),
+                "{prefix:?}"
+            );
+            assert_eq!(
+                Discovery::new_with_discovery_prefix(SERIAL_NUMBER, "scd4x", prefix),
+                Err(DiscoveryError::InvalidTopicPrefix),
+                "{prefix:?}"
+            );
+        }
+    }
+}
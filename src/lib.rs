@@ -9,57 +9,111 @@
 #![cfg_attr(not(doctest), doc = include_str!("../README.md"))]
 #![cfg_attr(not(test), no_std)]
 
+#[cfg(feature = "logger")]
+extern crate alloc;
+#[cfg(feature = "logger")]
+extern crate std;
+
+pub mod air_quality;
+
 #[cfg(feature = "async")]
 mod r#async;
 #[cfg(feature = "async")]
+pub use self::r#async::PressureCompensator as AsyncPressureCompensator;
+#[cfg(feature = "async")]
+pub use self::r#async::PressureSource as AsyncPressureSource;
+#[cfg(feature = "async")]
 pub use self::r#async::Scd4x as AsyncScd4x;
 
 #[cfg(feature = "blocking")]
 mod blocking;
 #[cfg(feature = "blocking")]
+pub use self::blocking::PressureCompensator;
+#[cfg(feature = "blocking")]
+pub use self::blocking::PressureSource;
+#[cfg(feature = "blocking")]
 pub use self::blocking::Scd4x;
 
+pub mod atmosphere;
+
+pub mod calibration;
+
+pub mod checksum;
+
 #[cfg(any(feature = "async", feature = "blocking"))]
-mod checksum;
-#[cfg(any(feature = "async", feature = "blocking"))]
+mod compensation;
 use self::checksum::compute as compute_checksum;
-#[cfg(any(feature = "async", feature = "blocking"))]
 use self::checksum::verify as verify_checksum;
 
-#[cfg(any(feature = "async", feature = "blocking"))]
+mod command;
+
+mod commands;
+
 mod constants;
-#[cfg(any(feature = "async", feature = "blocking"))]
 pub use self::constants::DEFAULT_ADDRESS;
 
-#[cfg(any(feature = "async", feature = "blocking"))]
 mod conversion;
 
-#[cfg(any(feature = "async", feature = "blocking"))]
+#[cfg(test)]
+mod datasheet;
+
+#[cfg(feature = "logger")]
+pub mod logger;
+
 mod error;
 #[cfg(any(feature = "async", feature = "blocking"))]
+pub use self::error::CompensationError;
+#[cfg(any(feature = "async", feature = "blocking", feature = "sim"))]
 pub use self::error::Error;
+pub use self::error::ProtocolError;
+
+pub mod filter;
+
+pub mod home_assistant;
+
+#[cfg(any(feature = "async", feature = "blocking"))]
+mod execution;
 
 #[cfg(any(feature = "async", feature = "blocking"))]
 mod macros;
 
 #[cfg(any(feature = "async", feature = "blocking"))]
-mod sample;
+mod pending;
 #[cfg(any(feature = "async", feature = "blocking"))]
+pub use self::pending::Pending;
+
+pub mod protocol;
+
+pub mod psychrometrics;
+
+mod sample;
+pub use self::sample::AbsoluteHumidity;
 pub use self::sample::Altitude;
-#[cfg(any(feature = "async", feature = "blocking"))]
 pub use self::sample::Co2;
-#[cfg(any(feature = "async", feature = "blocking"))]
+#[cfg(feature = "fixed-point")]
+pub use self::sample::FixedPointSample;
 pub use self::sample::Humidity;
-#[cfg(any(feature = "async", feature = "blocking"))]
+pub use self::sample::HumidityRatio;
+pub use self::sample::PartialSample;
 pub use self::sample::Pressure;
-#[cfg(any(feature = "async", feature = "blocking"))]
+pub use self::sample::RawSample;
 pub use self::sample::Sample;
-#[cfg(any(feature = "async", feature = "blocking"))]
 pub use self::sample::Temperature;
 
 #[cfg(any(feature = "async", feature = "blocking"))]
+mod sensor;
+
+#[cfg(feature = "sim")]
+pub mod sim;
+
+pub mod telemetry;
+
+pub mod trend;
+
 mod util;
 
+pub mod ventilation;
+
 #[cfg(any(feature = "async", feature = "blocking"))]
 mod state;
 #[cfg(any(feature = "async", feature = "blocking"))]
@@ -0,0 +1,73 @@
+// Copyright Claudio Mattera 2024-2026.
+//
+// Distributed under the MIT License or the Apache 2.0 License at your option.
+// See the accompanying files LICENSE-MIT.txt and LICENSE-APACHE-2.0.txt, or
+// online at
+// https://opensource.org/licenses/MIT
+// https://opensource.org/licenses/Apache-2.0
+
+//! Formats of logged records
+
+use core::fmt::Result as FmtResult;
+use core::fmt::Write;
+use core::time::Duration;
+
+use crate::sample::celsius_from_temperature;
+use crate::sample::number_from_humidity;
+use crate::sample::ppm_from_co2;
+use crate::Sample;
+
+use super::write_timestamp;
+
+/// Format of logged records
+///
+/// Timestamps are written as RFC 3339 date and time in UTC, CO₂ concentration
+/// in ppm, temperature in °C and relative humidity in %.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// Comma-separated values, with a header line at the start of each output
+    Csv,
+
+    /// JSON Lines, one JSON object per line
+    JsonLines,
+}
+
+impl Format {
+    /// Return the header written at the start of each output
+    #[must_use]
+    pub fn header(self) -> &'static str {
+        match self {
+            Self::Csv => "timestamp,co2_ppm,temperature_celsius,relative_humidity_percent\n",
+            Self::JsonLines => "",
+        }
+    }
+
+    /// Write a timestamped sample as a record, including the final newline
+    ///
+    /// # Errors
+    ///
+    /// Return an error if the output cannot be written.
+    pub fn write_record<W>(self, output: &mut W, timestamp: Duration, sample: &Sample) -> FmtResult
+    where
+        W: Write,
+    {
+        let co2 = ppm_from_co2(sample.co2);
+        let temperature = celsius_from_temperature(sample.temperature);
+        let humidity = number_from_humidity(sample.humidity);
+
+        match self {
+            Self::Csv => {
+                write_timestamp(output, timestamp)?;
+                writeln!(output, ",{co2:.0},{temperature:.2},{humidity:.2}")
+            }
+            Self::JsonLines => {
+                output.write_str("{\"timestamp\":\"")?;
+                write_timestamp(output, timestamp)?;
+                writeln!(
+                    output,
+                    "\",\"co2_ppm\":{co2:.0},\"temperature_celsius\":{temperature:.2},\"relative_humidity_percent\":{humidity:.2}}}"
+                )
+            }
+        }
+    }
+}
@@ -0,0 +1,564 @@
+// Copyright Claudio Mattera 2024-2026.
+//
+// Distributed under the MIT License or the Apache 2.0 License at your option.
+// See the accompanying files LICENSE-MIT.txt and LICENSE-APACHE-2.0.txt, or
+// online at
+// https://opensource.org/licenses/MIT
+// https://opensource.org/licenses/Apache-2.0
+
+//! Logger for writing timestamped samples to files or other outputs
+//!
+//! A [`SampleLogger`] wraps a sensor in measuring state, reads samples from
+//! it, and writes each of them together with a timestamp as a line of CSV or
+//! of [JSON Lines].
+//! The output is flushed after each record, so that a log is complete up to
+//! the last sample even if the program is interrupted.
+//!
+//! Outputs are opened through a [`Rotation`], which is asked for a new output
+//! whenever the current one would grow past a size limit.
+//! [`RotatingFiles`] creates numbered files next to each other, and any
+//! closure returning a [`std::io::Write`] can be used as well, e.g. for
+//! writing to standard output without any rotation.
+//!
+//! This module requires the standard library, so it is only available on
+//! hosted targets such as Linux, and enabling the feature `logger` makes the
+//! whole crate depend on `std`.
+//! It is not included in the documentation on docs.rs.
+//!
+//! ~~~~no_run
+//! # use embedded_hal_mock::eh1::delay::NoopDelay as Delay;
+//! # use embedded_hal_mock::eh1::i2c::Mock as I2cMock;
+//! # let i2c = I2cMock::new(&[]);
+//! # let delay = Delay;
+//! use scd4x_rs::logger::Format;
+//! use scd4x_rs::logger::RotatingFiles;
+//! use scd4x_rs::logger::SampleLogger;
+//! use scd4x_rs::Scd4x;
+//!
+//! let sensor = Scd4x::new(i2c, delay);
+//! let sensor = sensor.start_periodic_measurement()?;
+//!
+//! // Write samples to overnight.0.csv, overnight.1.csv, ... of at most 1 MiB
+//! let files = RotatingFiles::new("overnight.csv");
+//! let mut logger = SampleLogger::new(sensor, Format::Csv, files, Some(1 << 20));
+//!
+//! loop {
+//!     std::thread::sleep(std::time::Duration::from_secs(5));
+//!     logger.log()?;
+//! }
+//! # Ok::<(), scd4x_rs::logger::LoggerError>(())
+//! ~~~~
+//!
+//! [JSON Lines]: https://jsonlines.org/
+
+mod format;
+pub use self::format::Format;
+
+mod rotation;
+pub use self::rotation::RotatingFiles;
+pub use self::rotation::Rotation;
+
+use core::time::Duration;
+
+use alloc::string::String;
+
+use std::io::Error as IoError;
+use std::io::Write;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal::i2c::I2c;
+
+use crate::macros::debug;
+use crate::Error;
+use crate::Measuring;
+use crate::Sample;
+use crate::Scd4x;
+
+/// A source of wall-clock time for timestamps
+pub trait WallClock {
+    /// Return the time elapsed since the Unix epoch
+    fn now(&mut self) -> Duration;
+}
+
+impl<F> WallClock for F
+where
+    F: FnMut() -> Duration,
+{
+    fn now(&mut self) -> Duration {
+        self()
+    }
+}
+
+/// The system clock
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SystemClock;
+
+impl WallClock for SystemClock {
+    fn now(&mut self) -> Duration {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+    }
+}
+
+/// An error while logging samples
+#[derive(Debug)]
+pub enum LoggerError {
+    /// An error in the sensor
+    Sensor(Error),
+
+    /// An error while opening or writing an output
+    Io(IoError),
+}
+
+impl From<Error> for LoggerError {
+    fn from(error: Error) -> Self {
+        Self::Sensor(error)
+    }
+}
+
+impl From<IoError> for LoggerError {
+    fn from(error: IoError) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl core::error::Error for LoggerError {}
+
+impl core::fmt::Display for LoggerError {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(formatter, "{self:?}")
+    }
+}
+
+/// A logger writing timestamped samples from a sensor
+pub struct SampleLogger<I2C, D, R, C = SystemClock>
+where
+    R: Rotation,
+{
+    /// Sensor in measuring state
+    sensor: Scd4x<I2C, D, Measuring>,
+
+    /// Format of the records
+    format: Format,
+
+    /// Source of outputs
+    rotation: R,
+
+    /// Largest size of an output in bytes, if outputs are rotated
+    max_size: Option<u64>,
+
+    /// Source of timestamps
+    clock: C,
+
+    /// Current output, opened when the first record is written
+    output: Option<R::Writer>,
+
+    /// Index of the current output
+    index: u32,
+
+    /// Number of bytes written to the current output
+    written: u64,
+
+    /// Buffer for formatting a record
+    buffer: String,
+}
+
+impl<I2C, D, R> SampleLogger<I2C, D, R, SystemClock>
+where
+    I2C: I2c,
+    D: DelayNs,
+    R: Rotation,
+{
+    /// Create a new logger with timestamps from the system clock
+    ///
+    /// When a maximal size in bytes is given, a new output is opened
+    /// whenever a record would make the current one grow past it.
+    pub fn new(
+        sensor: Scd4x<I2C, D, Measuring>,
+        format: Format,
+        rotation: R,
+        max_size: Option<u64>,
+    ) -> Self {
+        Self::new_with_clock(sensor, format, rotation, max_size, SystemClock)
+    }
+}
+
+impl<I2C, D, R, C> SampleLogger<I2C, D, R, C>
+where
+    I2C: I2c,
+    D: DelayNs,
+    R: Rotation,
+    C: WallClock,
+{
+    /// Create a new logger with timestamps from a custom clock
+    pub fn new_with_clock(
+        sensor: Scd4x<I2C, D, Measuring>,
+        format: Format,
+        rotation: R,
+        max_size: Option<u64>,
+        clock: C,
+    ) -> Self {
+        Self {
+            sensor,
+            format,
+            rotation,
+            max_size,
+            clock,
+            output: None,
+            index: 0,
+            written: 0,
+            buffer: String::new(),
+        }
+    }
+
+    /// Read a sample from the sensor and write it
+    ///
+    /// The sensor produces a new sample every 5 seconds in periodic mode, or
+    /// every 30 seconds in low-power periodic mode.
+    /// Use [`SampleLogger::sensor()`] to query whether data is ready.
+    ///
+    /// # Errors
+    ///
+    /// Return an error if it cannot communicate with the sensor, or if the
+    /// record cannot be written.
+    pub fn log(&mut self) -> Result<Sample, LoggerError> {
+        let sample = self.sensor.read_measurement()?;
+        let timestamp = self.clock.now();
+        self.write(timestamp, &sample)?;
+        Ok(sample)
+    }
+
+    /// Write a sample with a timestamp
+    ///
+    /// The timestamp is expressed as time elapsed since the Unix epoch.
+    ///
+    /// # Errors
+    ///
+    /// Return an error if the record cannot be written.
+    pub fn write(&mut self, timestamp: Duration, sample: &Sample) -> Result<(), LoggerError> {
+        self.buffer.clear();
+        self.format
+            .write_record(&mut self.buffer, timestamp, sample)
+            .map_err(IoError::other)?;
+        let size = self.buffer.len() as u64;
+
+        let output = match self.output.take() {
+            Some(output) if self.needs_rotation(size) => {
+                drop(output);
+                self.index = self.index.saturating_add(1);
+                self.open()?
+            }
+            Some(output) => output,
+            None => self.open()?,
+        };
+        let output = self.output.insert(output);
+
+        output.write_all(self.buffer.as_bytes())?;
+        output.flush()?;
+        self.written = self.written.saturating_add(size);
+
+        Ok(())
+    }
+
+    /// Return a reference to the sensor
+    pub fn sensor(&mut self) -> &mut Scd4x<I2C, D, Measuring> {
+        &mut self.sensor
+    }
+
+    /// Return the index of the current output
+    #[must_use]
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Return the number of bytes written to the current output
+    #[must_use]
+    pub fn written(&self) -> u64 {
+        self.written
+    }
+
+    /// Release the sensor
+    #[must_use]
+    pub fn release(self) -> Scd4x<I2C, D, Measuring> {
+        self.sensor
+    }
+
+    /// Return whether a record would make the current output too large
+    ///
+    /// An output always contains at least one record, even if the record alone
+    /// is larger than the limit.
+    fn needs_rotation(&self, size: u64) -> bool {
+        let header = self.format.header().len() as u64;
+        self.max_size.is_some_and(|max_size| {
+            self.written > header && self.written.saturating_add(size) > max_size
+        })
+    }
+
+    /// Open the output for the current index and write the header
+    fn open(&mut self) -> Result<R::Writer, LoggerError> {
+        debug!("Open output {}", self.index);
+
+        let mut output = self.rotation.open(self.index)?;
+
+        let header = self.format.header();
+        output.write_all(header.as_bytes())?;
+        self.written = header.len() as u64;
+
+        Ok(output)
+    }
+}
+
+/// Write a timestamp as a RFC 3339 date and time in UTC with milliseconds
+fn write_timestamp<W>(output: &mut W, timestamp: Duration) -> core::fmt::Result
+where
+    W: core::fmt::Write,
+{
+    /// Number of seconds in a day
+    const SECONDS_PER_DAY: u64 = 86_400;
+
+    let seconds = timestamp.as_secs();
+    let (year, month, day) = civil_from_days(seconds / SECONDS_PER_DAY);
+    let seconds = seconds % SECONDS_PER_DAY;
+
+    write!(
+        output,
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60,
+        timestamp.subsec_millis(),
+    )
+}
+
+/// Convert a number of days since the Unix epoch to year, month and day
+///
+/// This is the algorithm `civil_from_days` by Howard Hinnant, restricted to
+/// dates after the Unix epoch.
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    #![expect(clippy::panic_in_result_fn, reason = "Assertions in tests")]
+
+    use super::*;
+
+    #[test]
+    fn test_write_timestamp() -> core::fmt::Result {
+        let cases = [
+            (Duration::ZERO, "1970-01-01T00:00:00.000Z"),
+            (Duration::from_secs(951_782_400), "2000-02-29T00:00:00.000Z"),
+            (
+                Duration::from_millis(1_767_225_599_999),
+                "2025-12-31T23:59:59.999Z",
+            ),
+            (
+                Duration::from_millis(1_792_333_357_250),
+                "2026-10-18T14:22:37.250Z",
+            ),
+        ];
+
+        for (timestamp, expected) in cases {
+            let mut output = String::new();
+            write_timestamp(&mut output, timestamp)?;
+            assert_eq!(output, expected);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "sim"))]
+mod golden_tests {
+    #![expect(clippy::panic_in_result_fn, reason = "Assertions in tests")]
+
+    use core::cell::Cell;
+    use core::cell::RefCell;
+
+    use alloc::rc::Rc;
+    use alloc::vec::Vec;
+
+    use std::io::Result as IoResult;
+
+    use super::*;
+
+    use crate::sample::co2_from_ppm;
+    use crate::sample::humidity_from_number;
+    use crate::sample::temperature_from_celsius;
+    use crate::sim::Simulator;
+    use crate::sim::VirtualClock;
+
+    /// Timestamp of 2026-01-01T00:00:00Z
+    const START: Duration = Duration::from_secs(1_767_225_600);
+
+    /// Interval between periodic measurements
+    const INTERVAL: Duration = Duration::from_secs(5);
+
+    /// An output shared with the test, counting flushes
+    #[derive(Clone, Default)]
+    struct SharedOutput {
+        /// Bytes written to the output
+        bytes: Rc<RefCell<Vec<u8>>>,
+
+        /// Number of bytes that were flushed
+        flushed: Rc<Cell<usize>>,
+    }
+
+    impl SharedOutput {
+        /// Return the bytes written to the output as a string
+        fn contents(&self) -> String {
+            String::from_utf8_lossy(&self.bytes.borrow()).into_owned()
+        }
+    }
+
+    impl Write for SharedOutput {
+        fn write(&mut self, bytes: &[u8]) -> IoResult<usize> {
+            self.bytes.borrow_mut().extend_from_slice(bytes);
+            Ok(bytes.len())
+        }
+
+        fn flush(&mut self) -> IoResult<()> {
+            self.flushed.set(self.bytes.borrow().len());
+            Ok(())
+        }
+    }
+
+    /// Indoor air with CO₂ concentration rising by 1 ppm per second
+    fn rising_co2(time: Duration) -> Sample {
+        #[expect(clippy::cast_precision_loss, reason = "Tests run for seconds")]
+        let seconds = time.as_secs() as f32;
+        Sample {
+            co2: co2_from_ppm(600.0 + seconds),
+            temperature: temperature_from_celsius(21.5),
+            humidity: humidity_from_number(42.25),
+        }
+    }
+
+    /// Log a number of periodic samples from a simulated sensor
+    fn log_samples(
+        format: Format,
+        max_size: Option<u64>,
+        count: usize,
+    ) -> Result<Vec<SharedOutput>, LoggerError> {
+        let clock = VirtualClock::new();
+        let mut simulator = Simulator::new(&clock, rising_co2);
+
+        let scd4x = Scd4x::new(&mut simulator, &clock);
+        let scd4x = scd4x.start_periodic_measurement()?;
+
+        let outputs = RefCell::new(Vec::new());
+        let rotation = |index: u32| -> IoResult<SharedOutput> {
+            assert_eq!(index as usize, outputs.borrow().len());
+            let output = SharedOutput::default();
+            outputs.borrow_mut().push(output.clone());
+            Ok(output)
+        };
+        let wall_clock = || START + clock.elapsed();
+
+        let mut logger =
+            SampleLogger::new_with_clock(scd4x, format, rotation, max_size, wall_clock);
+
+        for _ in 0..count {
+            clock.advance(INTERVAL);
+            logger.log()?;
+        }
+
+        let _scd4x = logger.release().stop_periodic_measurement()?;
+
+        Ok(outputs.into_inner())
+    }
+
+    #[test]
+    fn test_csv() -> Result<(), LoggerError> {
+        let outputs = log_samples(Format::Csv, None, 4)?;
+
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(outputs[0].contents(), include_str!("golden/samples.csv"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_json_lines() -> Result<(), LoggerError> {
+        let outputs = log_samples(Format::JsonLines, None, 4)?;
+
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(outputs[0].contents(), include_str!("golden/samples.jsonl"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_rotation() -> Result<(), LoggerError> {
+        // Room for the header of 66 bytes and two records of 41 bytes each
+        let outputs = log_samples(Format::Csv, Some(150), 5)?;
+
+        let contents: Vec<String> = outputs.iter().map(SharedOutput::contents).collect();
+        assert_eq!(
+            contents,
+            [
+                include_str!("golden/rotated.0.csv"),
+                include_str!("golden/rotated.1.csv"),
+                include_str!("golden/rotated.2.csv"),
+            ]
+        );
+        assert!(contents.iter().all(|content| content.len() <= 150));
+        Ok(())
+    }
+
+    #[test]
+    fn test_oversized_record() -> Result<(), LoggerError> {
+        let outputs = log_samples(Format::JsonLines, Some(10), 2)?;
+
+        let contents: Vec<String> = outputs.iter().map(SharedOutput::contents).collect();
+        let expected: Vec<&str> = include_str!("golden/samples.jsonl")
+            .split_inclusive('\n')
+            .take(2)
+            .collect();
+        assert_eq!(contents, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_flush_on_each_record() -> Result<(), LoggerError> {
+        let clock = VirtualClock::new();
+        let mut simulator = Simulator::new(&clock, rising_co2);
+
+        let scd4x = Scd4x::new(&mut simulator, &clock);
+        let scd4x = scd4x.start_periodic_measurement()?;
+
+        let output = SharedOutput::default();
+        let shared = output.clone();
+        let rotation = move |_index: u32| -> IoResult<SharedOutput> { Ok(shared.clone()) };
+        let wall_clock = || START + clock.elapsed();
+
+        let mut logger =
+            SampleLogger::new_with_clock(scd4x, Format::JsonLines, rotation, None, wall_clock);
+
+        for _ in 0..3_u32 {
+            clock.advance(INTERVAL);
+            logger.log()?;
+            assert_eq!(output.flushed.get(), output.bytes.borrow().len());
+            assert_eq!(logger.written(), output.bytes.borrow().len() as u64);
+        }
+
+        assert_eq!(logger.index(), 0);
+        Ok(())
+    }
+}
@@ -0,0 +1,105 @@
+// Copyright Claudio Mattera 2024-2026.
+//
+// Distributed under the MIT License or the Apache 2.0 License at your option.
+// See the accompanying files LICENSE-MIT.txt and LICENSE-APACHE-2.0.txt, or
+// online at
+// https://opensource.org/licenses/MIT
+// https://opensource.org/licenses/Apache-2.0
+
+//! Sources of logger outputs
+
+use alloc::string::ToString;
+
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::Result as IoResult;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// A source of outputs for a logger
+pub trait Rotation {
+    /// The type of outputs
+    type Writer: Write;
+
+    /// Open the output for an index
+    ///
+    /// Indices start from zero and increase by one at each rotation.
+    ///
+    /// # Errors
+    ///
+    /// Return an error if the output cannot be opened.
+    fn open(&mut self, index: u32) -> IoResult<Self::Writer>;
+}
+
+impl<F, W> Rotation for F
+where
+    F: FnMut(u32) -> IoResult<W>,
+    W: Write,
+{
+    type Writer = W;
+
+    fn open(&mut self, index: u32) -> IoResult<Self::Writer> {
+        self(index)
+    }
+}
+
+/// Numbered files next to each other
+///
+/// The index is inserted before the extension, so that a path `log.csv`
+/// results in files `log.0.csv`, `log.1.csv` and so on.
+/// Existing files are truncated.
+#[derive(Clone, Debug)]
+pub struct RotatingFiles {
+    /// Path of the files, before inserting the index
+    path: PathBuf,
+}
+
+impl RotatingFiles {
+    /// Create numbered files from a path
+    #[must_use]
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Return the path of the file for an index
+    #[must_use]
+    pub fn path(&self, index: u32) -> PathBuf {
+        let mut name = OsString::new();
+        if let Some(stem) = self.path.file_stem() {
+            name.push(stem);
+            name.push(".");
+        }
+        name.push(index.to_string());
+        if let Some(extension) = self.path.extension() {
+            name.push(".");
+            name.push(extension);
+        }
+        self.path.with_file_name(name)
+    }
+}
+
+impl Rotation for RotatingFiles {
+    type Writer = File;
+
+    fn open(&mut self, index: u32) -> IoResult<Self::Writer> {
+        File::create(self.path(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path() {
+        let files = RotatingFiles::new("logs/overnight.csv");
+        assert_eq!(files.path(0), PathBuf::from("logs/overnight.0.csv"));
+        assert_eq!(files.path(12), PathBuf::from("logs/overnight.12.csv"));
+
+        let files = RotatingFiles::new("samples");
+        assert_eq!(files.path(3), PathBuf::from("samples.3"));
+    }
+}
@@ -0,0 +1,55 @@
+// Copyright Claudio Mattera 2024-2026.
+//
+// Distributed under the MIT License or the Apache 2.0 License at your option.
+// See the accompanying files LICENSE-MIT.txt and LICENSE-APACHE-2.0.txt, or
+// online at
+// https://opensource.org/licenses/MIT
+// https://opensource.org/licenses/Apache-2.0
+
+//! Data types for commands whose execution is pending
+
+use core::time::Duration;
+
+/// A command that was sent to the sensor, but whose result was not read yet
+///
+/// A pending command is returned by the `begin_*()` functions of the sensor.
+/// The caller is free to do other work while the sensor is busy, and must
+/// call `finish()` on the sensor once [`Pending::duration()`] has elapsed.
+#[must_use = "the result of a pending command must be read with `finish()`"]
+#[derive(Debug)]
+pub struct Pending<T> {
+    /// Earliest duration after which the result is available
+    duration: Duration,
+
+    /// Expected response from the sensor
+    response: Response<T>,
+}
+
+/// Expected response from the sensor for a pending command
+#[derive(Debug)]
+pub enum Response<T> {
+    /// The command does not return any data
+    Empty(T),
+
+    /// The command returns a word, which is decoded by a function
+    Word(fn(u16) -> T),
+}
+
+impl<T> Pending<T> {
+    /// Create a new pending command
+    pub(crate) fn new(duration: Duration, response: Response<T>) -> Self {
+        Self { duration, response }
+    }
+
+    /// Return the earliest duration after which the result is available
+    ///
+    /// The duration is counted from the moment the command was sent.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Consume the pending command and return its expected response
+    pub(crate) fn into_response(self) -> Response<T> {
+        self.response
+    }
+}
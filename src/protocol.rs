@@ -0,0 +1,200 @@
+// Copyright Claudio Mattera 2024-2026.
+//
+// Distributed under the MIT License or the Apache 2.0 License at your option.
+// See the accompanying files LICENSE-MIT.txt and LICENSE-APACHE-2.0.txt, or
+// online at
+// https://opensource.org/licenses/MIT
+// https://opensource.org/licenses/Apache-2.0
+
+//! Sans-I/O implementation of the SCD4x protocol
+//!
+//! This module does not perform any I/O, and it is available regardless of
+//! the features `blocking` and `async`.
+//! It can be used to drive the sensor over any I²C implementation, e.g. Linux
+//! `i2c-dev`, a USB bridge or a DMA engine.
+//!
+//! Executing a command on the sensor consists of four steps:
+//!
+//! 1.  Write the buffer returned by [`Command::encode()`] to the sensor.
+//! 2.  Wait for [`Command::execution_time()`].
+//! 3.  Read [`Command::response_length()`] bytes from the sensor (if any).
+//! 4.  Decode the bytes with [`Command::decode()`].
+//!
+//! ~~~~
+//! use scd4x_rs::protocol::Command;
+//! use scd4x_rs::protocol::GetSerialNumber;
+//!
+//! let request = GetSerialNumber.encode(());
+//! assert_eq!(request.as_ref(), &[0x36, 0x82]);
+//!
+//! // Write the request, wait for the execution time, and read the response
+//! let response = [0xf8, 0x96, 0x31, 0x9f, 0x07, 0xc2, 0x3b, 0xbe, 0x89];
+//! assert_eq!(GetSerialNumber.response_length(), response.len());
+//!
+//! let serial_number = GetSerialNumber.decode(response)?;
+//! assert_eq!(serial_number, 0xf896_9f07_3bbe);
+//! # Ok::<(), scd4x_rs::ProtocolError>(())
+//! ~~~~
+
+use core::time::Duration;
+
+use crate::command::Command as Definition;
+use crate::command::SequenceType;
+use crate::ProtocolError;
+
+pub use crate::commands::GetAmbientPressure;
+pub use crate::commands::GetAutomaticSelfCalibrationEnabled;
+pub use crate::commands::GetDataReadyStatus;
+pub use crate::commands::GetSensorAltitude;
+pub use crate::commands::GetSerialNumber;
+pub use crate::commands::GetTemperatureOffset;
+pub use crate::commands::MeasureSingleShot;
+pub use crate::commands::MeasureSingleShotRhtOnly;
+pub use crate::commands::PerformFactoryReset;
+pub use crate::commands::PerformForcedRecalibration;
+pub use crate::commands::PerformSelfTest;
+pub use crate::commands::PersistSettings;
+pub use crate::commands::ReadMeasurement;
+pub use crate::commands::ReadMeasurementLenient;
+pub use crate::commands::ReadMeasurementRaw;
+pub use crate::commands::Reinitialize;
+pub use crate::commands::SetAmbientPressure;
+pub use crate::commands::SetAutomaticSelfCalibrationEnabled;
+pub use crate::commands::SetSensorAltitude;
+pub use crate::commands::SetTemperatureOffset;
+pub use crate::commands::StartLowPowerPeriodicMeasurement;
+pub use crate::commands::StartPeriodicMeasurement;
+pub use crate::commands::StopPeriodicMeasurement;
+
+/// A command to the sensor, independent of the I²C implementation
+pub trait Command {
+    /// The input type for the command
+    type Input;
+
+    /// The output type for the command
+    type Output;
+
+    /// The buffer to write to the sensor
+    type Request: AsRef<[u8]>;
+
+    /// The buffer to read from the sensor
+    type Response: AsRef<[u8]> + AsMut<[u8]> + Default;
+
+    /// Return the I²C register of the command
+    fn register(&self) -> u16;
+
+    /// Return the time the sensor needs to execute the command
+    ///
+    /// The response must not be read before this time has elapsed.
+    fn execution_time(&self) -> Duration;
+
+    /// Encode the command and its input to a buffer to write to the sensor
+    fn encode(&self, input: Self::Input) -> Self::Request;
+
+    /// Return the number of bytes to read from the sensor
+    ///
+    /// If the length is zero, nothing should be read from the sensor.
+    fn response_length(&self) -> usize {
+        Self::Response::default().as_ref().len()
+    }
+
+    /// Decode a buffer read from the sensor to the output
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a checksum does not match.
+    fn decode(&self, response: Self::Response) -> Result<Self::Output, ProtocolError>;
+}
+
+impl<C> Command for C
+where
+    C: Definition,
+{
+    type Input = C::Input;
+
+    type Output = C::Output;
+
+    type Request = <C::SequenceType as SequenceType>::Request;
+
+    type Response = <C::SequenceType as SequenceType>::Response;
+
+    fn register(&self) -> u16 {
+        Definition::register(self)
+    }
+
+    fn execution_time(&self) -> Duration {
+        self.max_duration()
+    }
+
+    fn encode(&self, input: Self::Input) -> Self::Request {
+        let input = self.preprocess(input);
+        C::SequenceType::encode(Definition::register(self), input)
+    }
+
+    fn decode(&self, response: Self::Response) -> Result<Self::Output, ProtocolError> {
+        let output = C::SequenceType::decode(response)?;
+        let output = self.postprocess(output);
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![expect(clippy::panic_in_result_fn, reason = "Assertions in tests")]
+
+    use super::*;
+
+    use crate::sample::co2_from_ppm;
+    use crate::sample::humidity_from_number;
+    use crate::sample::temperature_from_celsius;
+    use crate::Sample;
+
+    #[test]
+    fn test_encode_without_input() {
+        let request = StopPeriodicMeasurement.encode(());
+
+        assert_eq!(request.as_ref(), &[0x3f, 0x86]);
+        assert_eq!(StopPeriodicMeasurement.response_length(), 0);
+        assert_eq!(
+            StopPeriodicMeasurement.execution_time(),
+            Duration::from_millis(500)
+        );
+    }
+
+    #[test]
+    fn test_encode_with_input() {
+        let request = SetAutomaticSelfCalibrationEnabled.encode(true);
+
+        assert_eq!(request.as_ref(), &[0x24, 0x16, 0x00, 0x01, 0xb0]);
+        assert_eq!(SetAutomaticSelfCalibrationEnabled.response_length(), 0);
+    }
+
+    #[test]
+    fn test_decode_read_measurement() -> Result<(), ProtocolError> {
+        let request = ReadMeasurement.encode(());
+        assert_eq!(request.as_ref(), &[0xec, 0x05]);
+        assert_eq!(ReadMeasurement.response_length(), 9);
+
+        let response = [0x01, 0xf4, 0x33, 0x66, 0x67, 0xa2, 0x5e, 0xb9, 0x3c];
+        let sample = ReadMeasurement.decode(response)?;
+        let expected = Sample {
+            co2: co2_from_ppm(500.0),
+            temperature: temperature_from_celsius(25.001_602),
+            humidity: humidity_from_number(37.001_038),
+        };
+
+        assert_eq!(sample, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_perform_self_test() -> Result<(), ProtocolError> {
+        assert_eq!(PerformSelfTest.response_length(), 3);
+        assert_eq!(PerformSelfTest.execution_time(), Duration::from_secs(10));
+
+        let success = PerformSelfTest.decode([0x00, 0x00, 0x81])?;
+
+        assert!(success);
+        Ok(())
+    }
+}
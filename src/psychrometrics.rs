@@ -0,0 +1,350 @@
+// Copyright Claudio Mattera 2024-2026.
+//
+// Distributed under the MIT License or the Apache 2.0 License at your option.
+// See the accompanying files LICENSE-MIT.txt and LICENSE-APACHE-2.0.txt, or
+// online at
+// https://opensource.org/licenses/MIT
+// https://opensource.org/licenses/Apache-2.0
+
+//! Functions for derived psychrometric quantities
+//!
+//! The saturation vapour pressure over water is computed with the Magnus
+//! formula, using the coefficients from the Sensirion application note
+//! *Introduction to Humidity*.
+//!
+//! ~~~~
+//! use scd4x_rs::psychrometrics::dew_point;
+//! # #[cfg(not(feature = "uom"))]
+//! # {
+//!
+//! let dew_point = dew_point(25.0, 60.0);
+//! assert!((dew_point - 16.69).abs() < 0.01);
+//! # }
+//! ~~~~
+
+use libm::expf;
+use libm::fabsf;
+use libm::logf;
+use libm::sqrtf;
+
+use crate::sample::absolute_humidity_from_gram_per_cubic_meter;
+use crate::sample::celsius_from_temperature;
+use crate::sample::hectopascal_from_pressure;
+use crate::sample::humidity_from_number;
+use crate::sample::humidity_ratio_from_gram_per_kilogram;
+use crate::sample::number_from_humidity;
+use crate::sample::temperature_from_celsius;
+use crate::AbsoluteHumidity;
+use crate::Humidity;
+use crate::HumidityRatio;
+use crate::Pressure;
+use crate::Temperature;
+
+/// Magnus coefficient β (dimensionless)
+const MAGNUS_BETA: f32 = 17.62;
+
+/// Magnus coefficient λ in Celsius
+const MAGNUS_LAMBDA: f32 = 243.12;
+
+/// Saturation vapour pressure at 0 °C in hectoPascal
+const MAGNUS_PRESSURE: f32 = 6.112;
+
+/// Ratio of the molar masses of water and dry air
+const MOLAR_MASS_RATIO: f32 = 0.621_98;
+
+/// Ratio of the molar mass of water and the gas constant in g K / J
+const WATER_MOLAR_MASS_OVER_GAS_CONSTANT: f32 = 216.7;
+
+/// Absolute zero in Celsius
+const ABSOLUTE_ZERO: f32 = -273.15;
+
+/// Compute the dew point
+///
+/// The result is negative infinity for a relative humidity of zero.
+#[must_use]
+pub fn dew_point(temperature: Temperature, humidity: Humidity) -> Temperature {
+    let celsius = celsius_from_temperature(temperature);
+    let percent = number_from_humidity(humidity);
+
+    if percent <= 0.0 {
+        return temperature_from_celsius(f32::NEG_INFINITY);
+    }
+
+    let gamma = logf(percent / 100.0) + magnus_exponent(celsius);
+    let dew_point = MAGNUS_LAMBDA * gamma / (MAGNUS_BETA - gamma);
+
+    temperature_from_celsius(dew_point)
+}
+
+/// Compute the absolute humidity, i.e. the mass of water vapour per volume of air
+#[must_use]
+pub fn absolute_humidity(temperature: Temperature, humidity: Humidity) -> AbsoluteHumidity {
+    let celsius = celsius_from_temperature(temperature);
+    let vapour_pressure = vapour_pressure(celsius, number_from_humidity(humidity));
+
+    let gram_per_cubic_meter =
+        WATER_MOLAR_MASS_OVER_GAS_CONSTANT * vapour_pressure / (celsius - ABSOLUTE_ZERO);
+
+    absolute_humidity_from_gram_per_cubic_meter(gram_per_cubic_meter)
+}
+
+/// Compute the humidity ratio, i.e. the mass of water vapour per mass of dry air
+#[must_use]
+pub fn humidity_ratio(
+    temperature: Temperature,
+    humidity: Humidity,
+    pressure: Pressure,
+) -> HumidityRatio {
+    let celsius = celsius_from_temperature(temperature);
+    let vapour_pressure = vapour_pressure(celsius, number_from_humidity(humidity));
+    let hectopascal = hectopascal_from_pressure(pressure);
+
+    let gram_per_kilogram =
+        1000.0 * MOLAR_MASS_RATIO * vapour_pressure / (hectopascal - vapour_pressure);
+
+    humidity_ratio_from_gram_per_kilogram(gram_per_kilogram)
+}
+
+/// Compute the heat index, i.e. the apparent temperature
+///
+/// The heat index is computed with the algorithm of the US National Weather
+/// Service, which combines the Steadman approximation with the Rothfusz
+/// regression and its adjustments.
+#[must_use]
+pub fn heat_index(temperature: Temperature, humidity: Humidity) -> Temperature {
+    let fahrenheit = celsius_from_temperature(temperature) * 1.8 + 32.0;
+    let percent = number_from_humidity(humidity);
+
+    let simple = 0.5 * (fahrenheit + 61.0 + (fahrenheit - 68.0) * 1.2 + percent * 0.094);
+
+    let heat_index = if (simple + fahrenheit) / 2.0 < 80.0 {
+        simple
+    } else {
+        let regression = -42.379 + 2.049_015_3 * fahrenheit + 10.143_331 * percent
+            - 0.224_755_4 * fahrenheit * percent
+            - 0.006_837_83 * fahrenheit * fahrenheit
+            - 0.054_817_17 * percent * percent
+            + 0.001_228_74 * fahrenheit * fahrenheit * percent
+            + 0.000_852_82 * fahrenheit * percent * percent
+            - 0.000_001_99 * fahrenheit * fahrenheit * percent * percent;
+
+        if percent < 13.0 && (80.0..=112.0).contains(&fahrenheit) {
+            regression - (13.0 - percent) / 4.0 * sqrtf((17.0 - fabsf(fahrenheit - 95.0)) / 17.0)
+        } else if percent > 85.0 && (80.0..=87.0).contains(&fahrenheit) {
+            regression + (percent - 85.0) / 10.0 * (87.0 - fahrenheit) / 5.0
+        } else {
+            regression
+        }
+    };
+
+    temperature_from_celsius((heat_index - 32.0) / 1.8)
+}
+
+/// Re-express a relative humidity at a different temperature
+///
+/// The absolute humidity is kept constant, i.e. the amount of water vapour in
+/// the air does not change, only its temperature does.
+/// The result is not clamped, so it can exceed 100 % if the new temperature is
+/// below the dew point.
+#[must_use]
+pub fn relative_humidity_at(
+    temperature: Temperature,
+    humidity: Humidity,
+    new_temperature: Temperature,
+) -> Humidity {
+    let celsius = celsius_from_temperature(temperature);
+    let new_celsius = celsius_from_temperature(new_temperature);
+    let percent = number_from_humidity(humidity);
+
+    let saturation_pressure = vapour_pressure(celsius, 100.0);
+    let new_saturation_pressure = vapour_pressure(new_celsius, 100.0);
+    let temperature_ratio = (new_celsius - ABSOLUTE_ZERO) / (celsius - ABSOLUTE_ZERO);
+
+    let new_percent = percent * saturation_pressure / new_saturation_pressure * temperature_ratio;
+
+    humidity_from_number(new_percent)
+}
+
+/// Compute the exponent of the Magnus formula
+fn magnus_exponent(celsius: f32) -> f32 {
+    MAGNUS_BETA * celsius / (MAGNUS_LAMBDA + celsius)
+}
+
+/// Compute the water vapour pressure in hectoPascal
+fn vapour_pressure(celsius: f32, percent: f32) -> f32 {
+    percent / 100.0 * MAGNUS_PRESSURE * expf(magnus_exponent(celsius))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::conversion::word_to_humidity;
+    use crate::sample::gram_per_cubic_meter_from_absolute_humidity;
+    use crate::sample::gram_per_kilogram_from_humidity_ratio;
+    use crate::sample::pressure_from_hectopascal;
+
+    /// Dew point in Celsius for temperature in Celsius and relative humidity
+    const DEW_POINT_TABLE: [(f32, f32, f32); 6] = [
+        (0.0, 70.0, -4.8),
+        (10.0, 90.0, 8.4),
+        (20.0, 50.0, 9.3),
+        (25.0, 60.0, 16.7),
+        (30.0, 80.0, 26.2),
+        (40.0, 30.0, 19.1),
+    ];
+
+    /// Absolute humidity in g/m³ for temperature in Celsius and relative humidity
+    const ABSOLUTE_HUMIDITY_TABLE: [(f32, f32, f32); 6] = [
+        (0.0, 100.0, 4.85),
+        (10.0, 100.0, 9.40),
+        (20.0, 100.0, 17.3),
+        (20.0, 50.0, 8.65),
+        (25.0, 100.0, 23.0),
+        (30.0, 100.0, 30.4),
+    ];
+
+    /// Humidity ratio in g/kg for temperature in Celsius and relative humidity
+    /// at standard pressure
+    const HUMIDITY_RATIO_TABLE: [(f32, f32, f32); 5] = [
+        (10.0, 100.0, 7.66),
+        (20.0, 50.0, 7.26),
+        (20.0, 100.0, 14.7),
+        (25.0, 50.0, 9.88),
+        (30.0, 100.0, 27.3),
+    ];
+
+    /// Heat index in Fahrenheit for temperature in Fahrenheit and relative
+    /// humidity, from the US National Weather Service chart
+    const HEAT_INDEX_TABLE: [(f32, f32, f32); 6] = [
+        (80.0, 40.0, 80.0),
+        (86.0, 90.0, 105.0),
+        (90.0, 50.0, 95.0),
+        (96.0, 65.0, 121.0),
+        (100.0, 40.0, 109.0),
+        (104.0, 55.0, 137.0),
+    ];
+
+    #[test]
+    fn test_dew_point() {
+        for (celsius, percent, expected) in DEW_POINT_TABLE {
+            let actual = dew_point(
+                temperature_from_celsius(celsius),
+                humidity_from_number(percent),
+            );
+            let actual = celsius_from_temperature(actual);
+
+            assert!(
+                (actual - expected).abs() < 0.1,
+                "dew point at {celsius} °C and {percent} %: {actual} != {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_dew_point_saturated() {
+        let actual = dew_point(temperature_from_celsius(21.5), humidity_from_number(100.0));
+        let actual = celsius_from_temperature(actual);
+
+        assert!((actual - 21.5).abs() < 0.001, "{actual} != 21.5");
+    }
+
+    #[test]
+    fn test_dew_point_dry() {
+        let humidity = word_to_humidity(0x0000);
+
+        let actual = dew_point(temperature_from_celsius(21.5), humidity);
+        let actual = celsius_from_temperature(actual);
+
+        assert!(
+            actual.is_infinite() && actual.is_sign_negative(),
+            "{actual} != -inf"
+        );
+    }
+
+    #[test]
+    fn test_absolute_humidity() {
+        for (celsius, percent, expected) in ABSOLUTE_HUMIDITY_TABLE {
+            let actual = absolute_humidity(
+                temperature_from_celsius(celsius),
+                humidity_from_number(percent),
+            );
+            let actual = gram_per_cubic_meter_from_absolute_humidity(actual);
+
+            assert!(
+                (actual - expected).abs() / expected < 0.01,
+                "absolute humidity at {celsius} °C and {percent} %: {actual} != {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_humidity_ratio() {
+        let pressure = pressure_from_hectopascal(1013.25);
+
+        for (celsius, percent, expected) in HUMIDITY_RATIO_TABLE {
+            let actual = humidity_ratio(
+                temperature_from_celsius(celsius),
+                humidity_from_number(percent),
+                pressure,
+            );
+            let actual = gram_per_kilogram_from_humidity_ratio(actual);
+
+            assert!(
+                (actual - expected).abs() / expected < 0.01,
+                "humidity ratio at {celsius} °C and {percent} %: {actual} != {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_relative_humidity_at() {
+        let temperature = temperature_from_celsius(25.0);
+        let humidity = humidity_from_number(50.0);
+
+        let same = relative_humidity_at(temperature, humidity, temperature);
+        let same = number_from_humidity(same);
+        assert!((same - 50.0).abs() < 0.001, "{same} != 50");
+
+        for new_celsius in [15.0, 22.0, 28.0, 35.0] {
+            let new_temperature = temperature_from_celsius(new_celsius);
+            let new_humidity = relative_humidity_at(temperature, humidity, new_temperature);
+
+            let before = gram_per_cubic_meter_from_absolute_humidity(absolute_humidity(
+                temperature,
+                humidity,
+            ));
+            let after = gram_per_cubic_meter_from_absolute_humidity(absolute_humidity(
+                new_temperature,
+                new_humidity,
+            ));
+
+            assert!(
+                (before - after).abs() < 0.001,
+                "absolute humidity at {new_celsius} °C: {after} != {before}"
+            );
+        }
+
+        let colder = relative_humidity_at(temperature, humidity, temperature_from_celsius(22.5));
+        let colder = number_from_humidity(colder);
+        assert!((colder - 57.63).abs() < 0.01, "{colder} != 57.63");
+    }
+
+    #[test]
+    fn test_heat_index() {
+        for (fahrenheit, percent, expected) in HEAT_INDEX_TABLE {
+            let celsius = (fahrenheit - 32.0) / 1.8;
+            let actual = heat_index(
+                temperature_from_celsius(celsius),
+                humidity_from_number(percent),
+            );
+            let actual = celsius_from_temperature(actual) * 1.8 + 32.0;
+
+            // The chart is rounded to whole degrees
+            assert!(
+                (actual - expected).abs() <= 0.6,
+                "heat index at {fahrenheit} °F and {percent} %: {actual} != {expected}"
+            );
+        }
+    }
+}
@@ -8,23 +8,38 @@
 
 //! Data types and functions for SCD4x sensor samples
 
+#[cfg(feature = "fixed-point")]
+use crate::conversion::word_to_millicelsius;
+#[cfg(feature = "fixed-point")]
+use crate::conversion::word_to_millipercent;
+use crate::conversion::words_to_sample;
+use crate::psychrometrics;
+
 #[cfg(feature = "uom")]
 use uom::si::f32::Length as UomAltitude;
 #[cfg(feature = "uom")]
+use uom::si::f32::MassDensity as UomAbsoluteHumidity;
+#[cfg(feature = "uom")]
 use uom::si::f32::Pressure as UomPressure;
 #[cfg(feature = "uom")]
 use uom::si::f32::Ratio as UomHumidity;
 #[cfg(feature = "uom")]
+use uom::si::f32::Ratio as UomHumidityRatio;
+#[cfg(feature = "uom")]
 use uom::si::f32::Ratio as UomCo2;
 #[cfg(feature = "uom")]
 use uom::si::f32::ThermodynamicTemperature as UomTemperature;
 #[cfg(feature = "uom")]
 use uom::si::length::meter;
 #[cfg(feature = "uom")]
+use uom::si::mass_density::gram_per_cubic_meter;
+#[cfg(feature = "uom")]
 use uom::si::pressure::hectopascal;
 #[cfg(feature = "uom")]
 use uom::si::ratio::part_per_million;
 #[cfg(feature = "uom")]
+use uom::si::ratio::per_mille;
+#[cfg(feature = "uom")]
 use uom::si::ratio::percent;
 #[cfg(feature = "uom")]
 use uom::si::thermodynamic_temperature::degree_celsius;
@@ -49,6 +64,14 @@
 /// Type for altitude values
 pub type Altitude = UomAltitude;
 
+#[cfg(feature = "uom")]
+/// Type for absolute humidity values
+pub type AbsoluteHumidity = UomAbsoluteHumidity;
+
+#[cfg(feature = "uom")]
+/// Type for humidity ratio values
+pub type HumidityRatio = UomHumidityRatio;
+
 #[cfg(feature = "uom")]
 /// Convert a raw value in PPM to a CO₂ concentration
 pub(crate) fn co2_from_ppm(raw: f32) -> Co2 {
@@ -79,14 +102,19 @@ pub(crate) fn humidity_from_number(raw: f32) -> Humidity {
     Humidity::new::<percent>(raw)
 }
 
+#[cfg(feature = "uom")]
+/// Convert a humidity to a raw value in percent
+pub(crate) fn number_from_humidity(humidity: Humidity) -> f32 {
+    humidity.get::<percent>()
+}
+
 #[cfg(feature = "uom")]
 /// Convert a pressure to a raw value in hectoPascal
 pub(crate) fn hectopascal_from_pressure(pressure: Pressure) -> f32 {
     pressure.get::<hectopascal>()
 }
 
-#[cfg(all(feature = "uom", any(feature = "blocking", feature = "async")))]
-#[cfg(test)]
+#[cfg(feature = "uom")]
 /// Convert a raw value in hectoPascal to a pressure
 pub(crate) fn pressure_from_hectopascal(raw: f32) -> Pressure {
     Pressure::new::<hectopascal>(raw)
@@ -104,6 +132,34 @@ pub(crate) fn meter_from_altitude(altitude: Altitude) -> f32 {
     altitude.get::<meter>()
 }
 
+#[cfg(feature = "uom")]
+/// Convert a raw value in gram per cubic meter to an absolute humidity
+pub(crate) fn absolute_humidity_from_gram_per_cubic_meter(raw: f32) -> AbsoluteHumidity {
+    AbsoluteHumidity::new::<gram_per_cubic_meter>(raw)
+}
+
+#[cfg(feature = "uom")]
+#[cfg(test)]
+/// Convert an absolute humidity to a raw value in gram per cubic meter
+pub(crate) fn gram_per_cubic_meter_from_absolute_humidity(
+    absolute_humidity: AbsoluteHumidity,
+) -> f32 {
+    absolute_humidity.get::<gram_per_cubic_meter>()
+}
+
+#[cfg(feature = "uom")]
+/// Convert a raw value in gram per kilogram to a humidity ratio
+pub(crate) fn humidity_ratio_from_gram_per_kilogram(raw: f32) -> HumidityRatio {
+    HumidityRatio::new::<per_mille>(raw)
+}
+
+#[cfg(feature = "uom")]
+#[cfg(test)]
+/// Convert a humidity ratio to a raw value in gram per kilogram
+pub(crate) fn gram_per_kilogram_from_humidity_ratio(humidity_ratio: HumidityRatio) -> f32 {
+    humidity_ratio.get::<per_mille>()
+}
+
 #[cfg(not(feature = "uom"))]
 /// Type for CO₂ values
 pub type Co2 = f32;
@@ -124,6 +180,14 @@ pub(crate) fn meter_from_altitude(altitude: Altitude) -> f32 {
 /// Type for altitude values
 pub type Altitude = f32;
 
+#[cfg(not(feature = "uom"))]
+/// Type for absolute humidity values
+pub type AbsoluteHumidity = f32;
+
+#[cfg(not(feature = "uom"))]
+/// Type for humidity ratio values
+pub type HumidityRatio = f32;
+
 #[cfg(not(feature = "uom"))]
 /// Convert a raw value in Celsius to a temperature
 pub(crate) fn co2_from_ppm(raw: f32) -> Co2 {
@@ -154,14 +218,19 @@ pub(crate) fn humidity_from_number(raw: f32) -> Humidity {
     raw
 }
 
+#[cfg(not(feature = "uom"))]
+/// Convert a humidity to a raw value in percent
+pub(crate) fn number_from_humidity(humidity: Humidity) -> f32 {
+    humidity
+}
+
 #[cfg(not(feature = "uom"))]
 /// Convert a pressure to a raw value in hectoPascal
 pub(crate) fn hectopascal_from_pressure(pressure: Pressure) -> f32 {
     pressure
 }
 
-#[cfg(all(not(feature = "uom"), any(feature = "blocking", feature = "async")))]
-#[cfg(test)]
+#[cfg(not(feature = "uom"))]
 /// Convert a raw value in hectoPascal to a pressure
 pub(crate) fn pressure_from_hectopascal(raw: f32) -> Pressure {
     raw
@@ -179,6 +248,45 @@ pub(crate) fn meter_from_altitude(altitude: Altitude) -> f32 {
     altitude
 }
 
+#[cfg(not(feature = "uom"))]
+/// Convert a raw value in gram per cubic meter to an absolute humidity
+pub(crate) fn absolute_humidity_from_gram_per_cubic_meter(raw: f32) -> AbsoluteHumidity {
+    raw
+}
+
+#[cfg(not(feature = "uom"))]
+#[cfg(test)]
+/// Convert an absolute humidity to a raw value in gram per cubic meter
+pub(crate) fn gram_per_cubic_meter_from_absolute_humidity(
+    absolute_humidity: AbsoluteHumidity,
+) -> f32 {
+    absolute_humidity
+}
+
+#[cfg(not(feature = "uom"))]
+/// Convert a raw value in gram per kilogram to a humidity ratio
+pub(crate) fn humidity_ratio_from_gram_per_kilogram(raw: f32) -> HumidityRatio {
+    raw
+}
+
+#[cfg(not(feature = "uom"))]
+#[cfg(test)]
+/// Convert a humidity ratio to a raw value in gram per kilogram
+pub(crate) fn gram_per_kilogram_from_humidity_ratio(humidity_ratio: HumidityRatio) -> f32 {
+    humidity_ratio
+}
+
+#[cfg(test)]
+/// Create a sample from raw values in parts per million, degrees Celsius and
+/// percent
+pub(crate) fn sample(co2: f32, temperature: f32, humidity: f32) -> Sample {
+    Sample {
+        co2: co2_from_ppm(co2),
+        temperature: temperature_from_celsius(temperature),
+        humidity: humidity_from_number(humidity),
+    }
+}
+
 /// A full sample: CO₂ concentration, temperature and humidity
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
 pub struct Sample {
@@ -191,3 +299,124 @@ pub struct Sample {
     /// Humidity
     pub humidity: Humidity,
 }
+
+impl Sample {
+    /// Compute the dew point
+    ///
+    /// See [`psychrometrics::dew_point()`].
+    #[must_use]
+    pub fn dew_point(&self) -> Temperature {
+        psychrometrics::dew_point(self.temperature, self.humidity)
+    }
+
+    /// Compute the absolute humidity
+    ///
+    /// See [`psychrometrics::absolute_humidity()`].
+    #[must_use]
+    pub fn absolute_humidity(&self) -> AbsoluteHumidity {
+        psychrometrics::absolute_humidity(self.temperature, self.humidity)
+    }
+
+    /// Compute the humidity ratio at the given pressure
+    ///
+    /// See [`psychrometrics::humidity_ratio()`].
+    #[must_use]
+    pub fn humidity_ratio(&self, pressure: Pressure) -> HumidityRatio {
+        psychrometrics::humidity_ratio(self.temperature, self.humidity, pressure)
+    }
+
+    /// Compute the heat index
+    ///
+    /// See [`psychrometrics::heat_index()`].
+    #[must_use]
+    pub fn heat_index(&self) -> Temperature {
+        psychrometrics::heat_index(self.temperature, self.humidity)
+    }
+}
+
+/// A raw sample, as read from the sensor
+///
+/// The fields contain the words returned by the sensor, before any conversion.
+/// A raw sample can be converted to a [`Sample`] with [`From`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RawSample {
+    /// CO₂ concentration in ppm
+    pub co2: u16,
+
+    /// Temperature ticks
+    pub temperature: u16,
+
+    /// Humidity ticks
+    pub humidity: u16,
+}
+
+impl From<RawSample> for Sample {
+    fn from(raw: RawSample) -> Self {
+        words_to_sample(raw.co2, raw.temperature, raw.humidity)
+    }
+}
+
+#[cfg(feature = "fixed-point")]
+/// A full sample in fixed-point representation
+///
+/// The values are computed with integer arithmetic only, and they are equal to
+/// the values of a [`Sample`] multiplied by 1000 and rounded half away from
+/// zero.
+/// A fixed-point sample can be converted from a [`RawSample`] with [`From`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct FixedPointSample {
+    /// CO₂ concentration in ppm
+    pub co2: u16,
+
+    /// Temperature in thousandths of Celsius
+    pub temperature: i32,
+
+    /// Humidity in thousandths of percent
+    pub humidity: u32,
+}
+
+#[cfg(feature = "fixed-point")]
+impl From<RawSample> for FixedPointSample {
+    fn from(raw: RawSample) -> Self {
+        Self {
+            co2: raw.co2,
+            temperature: word_to_millicelsius(raw.temperature),
+            humidity: word_to_millipercent(raw.humidity),
+        }
+    }
+}
+
+/// A sample whose fields are validated independently
+///
+/// A field is `None` if its checksum did not match.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct PartialSample {
+    /// CO₂ concentration
+    pub co2: Option<Co2>,
+
+    /// Temperature
+    pub temperature: Option<Temperature>,
+
+    /// Humidity
+    pub humidity: Option<Humidity>,
+}
+
+impl PartialSample {
+    /// Return the number of invalid fields
+    #[must_use]
+    pub fn invalid_fields(&self) -> u32 {
+        u32::from(self.co2.is_none())
+            + u32::from(self.temperature.is_none())
+            + u32::from(self.humidity.is_none())
+    }
+
+    /// Convert to a full sample, if all fields are valid
+    #[must_use]
+    pub fn into_sample(self) -> Option<Sample> {
+        Some(Sample {
+            co2: self.co2?,
+            temperature: self.temperature?,
+            humidity: self.humidity?,
+        })
+    }
+}
@@ -0,0 +1,1557 @@
+// Copyright Claudio Mattera 2024-2026.
+//
+// Distributed under the MIT License or the Apache 2.0 License at your option.
+// See the accompanying files LICENSE-MIT.txt and LICENSE-APACHE-2.0.txt, or
+// online at
+// https://opensource.org/licenses/MIT
+// https://opensource.org/licenses/Apache-2.0
+
+//! Macro for SCD4x sensor interface
+//!
+//! The blocking and the asynchronous sensors only differ in the HAL crate and
+//! in the `async` and `.await` keywords, so both are generated from the same
+//! source, together with their tests.
+
+/// Define the sensor interface and its tests
+///
+/// The macro must be invoked either as `define_sensor!(blocking)` or as
+/// `define_sensor!(async)`, in a module next to a module `execution` defined
+/// with the corresponding invocation of
+/// [`define_execution`](crate::execution::define_execution).
+macro_rules! define_sensor {
+    (blocking) => {
+        $crate::sensor::define_sensor!(@impl embedded_hal, [], [], [test]);
+    };
+    (async) => {
+        $crate::sensor::define_sensor!(@impl embedded_hal_async, [async], [.await], [tokio::test]);
+    };
+    (@impl $hal:ident, [$($async:tt)*], [$($await:tt)*], [$($test:tt)*]) => {
+        use core::marker::PhantomData;
+
+        use $hal::delay::DelayNs;
+        use $hal::i2c::I2c;
+
+        use $crate::atmosphere::check_consistency;
+        use $crate::commands;
+        use $crate::constants::DEFAULT_ADDRESS;
+        use $crate::macros::debug;
+        #[cfg(feature = "fixed-point")]
+        use $crate::sample::FixedPointSample;
+        use $crate::sample::PartialSample;
+        use $crate::sample::RawSample;
+        use $crate::sample::Sample;
+        use $crate::Altitude;
+        use $crate::Co2;
+        use $crate::Error;
+        use $crate::Idle;
+        use $crate::Measuring;
+        use $crate::Pending;
+        use $crate::Pressure;
+        use $crate::State;
+        use $crate::Temperature;
+
+        use super::execution::finish;
+        use super::execution::ExecuteCommand;
+
+        /// A sensor in a new state, and the pending command causing the transition
+        type PendingTransition<I2C, D, S> = (Scd4x<I2C, D, S>, Pending<()>);
+
+        /// Interface to SCD4x sensor over I²C
+        pub struct Scd4x<I2c, Delay, State> {
+            /// I²C device
+            i2c: I2c,
+
+            /// I²C address
+            address: u8,
+
+            /// Delay function
+            delay: Delay,
+
+            /// Number of checksum failures while reading measurements
+            checksum_failures: u32,
+
+            /// State for type-state pattern
+            _state: PhantomData<State>,
+        }
+
+        impl<I2C, D> Scd4x<I2C, D, Idle>
+        where
+            I2C: I2c,
+            D: DelayNs,
+        {
+            /// Create a new sensor using an I²C interface and a delay function using
+            /// the sensor's default address [`DEFAULT_ADDRESS`])
+            pub fn new(i2c: I2C, delay: D) -> Self {
+                Self::new_with_address(i2c, DEFAULT_ADDRESS, delay)
+            }
+
+            /// Create a new sensor using an I²C interface and a delay function using
+            /// a custom address
+            pub fn new_with_address(i2c: I2C, address: u8, delay: D) -> Self {
+                Self {
+                    i2c,
+                    address,
+                    delay,
+                    checksum_failures: 0,
+                    _state: PhantomData,
+                }
+            }
+
+            /// Start periodic measurement
+            ///
+            /// # Errors
+            ///
+            /// Return an error if it cannot communicate with the sensor.
+            pub $($async)* fn start_periodic_measurement(
+                mut self,
+            ) -> Result<Scd4x<I2C, D, Measuring>, Error> {
+                debug!("Send command 'start_periodic_measurement'");
+
+                commands::StartPeriodicMeasurement
+                    .execute(self.address, &mut self.i2c, &mut self.delay, ())
+                    $($await)*?;
+
+                Ok(Scd4x {
+                    i2c: self.i2c,
+                    address: self.address,
+                    delay: self.delay,
+                    checksum_failures: self.checksum_failures,
+                    _state: PhantomData,
+                })
+            }
+
+            /// Set temperature offset
+            ///
+            /// # Errors
+            ///
+            /// Return an error if it cannot communicate with the sensor.
+            pub $($async)* fn set_temperature_offset(
+                &mut self,
+                temperature_offset: Temperature,
+            ) -> Result<(), Error> {
+                debug!("Send command 'set_temperature_offset'");
+
+                commands::SetTemperatureOffset
+                    .execute(
+                        self.address,
+                        &mut self.i2c,
+                        &mut self.delay,
+                        temperature_offset,
+                    )
+                    $($await)*
+            }
+
+            /// Get temperature offset
+            ///
+            /// # Errors
+            ///
+            /// Return an error if it cannot communicate with the sensor.
+            pub $($async)* fn get_temperature_offset(&mut self) -> Result<Temperature, Error> {
+                debug!("Send command 'get_temperature_offset'");
+
+                commands::GetTemperatureOffset
+                    .execute(self.address, &mut self.i2c, &mut self.delay, ())
+                    $($await)*
+            }
+
+            /// Set sensor altitude
+            ///
+            /// # Errors
+            ///
+            /// Return an error if it cannot communicate with the sensor.
+            pub $($async)* fn set_sensor_altitude(
+                &mut self,
+                sensor_altitude: Altitude,
+            ) -> Result<(), Error> {
+                debug!("Send command 'set_sensor_altitude'");
+
+                commands::SetSensorAltitude
+                    .execute(
+                        self.address,
+                        &mut self.i2c,
+                        &mut self.delay,
+                        sensor_altitude,
+                    )
+                    $($await)*
+            }
+
+            /// Get sensor altitude
+            ///
+            /// # Errors
+            ///
+            /// Return an error if it cannot communicate with the sensor.
+            pub $($async)* fn get_sensor_altitude(&mut self) -> Result<Altitude, Error> {
+                debug!("Send command 'get_sensor_altitude'");
+
+                commands::GetSensorAltitude
+                    .execute(self.address, &mut self.i2c, &mut self.delay, ())
+                    $($await)*
+            }
+
+            /// Check that an ambient pressure agrees with the sensor altitude
+            ///
+            /// When an ambient pressure is set, the sensor ignores its altitude.
+            /// This function reads the sensor altitude, and it compares the
+            /// standard-atmosphere pressure at that altitude with the ambient
+            /// pressure.
+            /// Return whether they differ by at most the tolerance, and log a
+            /// message if they do not.
+            ///
+            /// # Errors
+            ///
+            /// Return an error if it cannot communicate with the sensor.
+            pub $($async)* fn check_ambient_pressure(
+                &mut self,
+                ambient_pressure: Pressure,
+                tolerance: Pressure,
+            ) -> Result<bool, Error> {
+                let altitude = self.get_sensor_altitude()$($await)*?;
+
+                let consistent = check_consistency(altitude, ambient_pressure, tolerance).is_ok();
+                if !consistent {
+                    debug!(
+                        "Ambient pressure {} hPa disagrees with sensor altitude {} m",
+                        $crate::sample::hectopascal_from_pressure(ambient_pressure),
+                        $crate::sample::meter_from_altitude(altitude)
+                    );
+                }
+
+                Ok(consistent)
+            }
+
+            /// Perform forced recalibration
+            ///
+            /// # Errors
+            ///
+            /// Return an error if it cannot communicate with the sensor.
+            pub $($async)* fn perform_forced_recalibration(
+                &mut self,
+                co2: Co2,
+            ) -> Result<Option<Co2>, Error> {
+                debug!("Send command 'perform_forced_recalibration'");
+
+                commands::PerformForcedRecalibration
+                    .execute(self.address, &mut self.i2c, &mut self.delay, co2)
+                    $($await)*
+            }
+
+            /// Begin performing forced recalibration
+            ///
+            /// The command is sent without waiting for its completion, and its result
+            /// must be read with [`Scd4x::finish()`] once [`Pending::duration()`] has
+            /// elapsed.
+            ///
+            /// # Errors
+            ///
+            /// Return an error if it cannot communicate with the sensor.
+            pub $($async)* fn begin_perform_forced_recalibration(
+                &mut self,
+                co2: Co2,
+            ) -> Result<Pending<Option<Co2>>, Error> {
+                debug!("Begin command 'perform_forced_recalibration'");
+
+                commands::PerformForcedRecalibration
+                    .begin(self.address, &mut self.i2c, co2)
+                    $($await)*
+            }
+
+            /// Set whether automatic self-calibration is enabled
+            ///
+            /// # Errors
+            ///
+            /// Return an error if it cannot communicate with the sensor.
+            pub $($async)* fn set_automatic_self_calibration_enabled(
+                &mut self,
+                enabled: bool,
+            ) -> Result<(), Error> {
+                debug!("Send command 'set_automatic_self_calibration_enabled'");
+
+                commands::SetAutomaticSelfCalibrationEnabled
+                    .execute(self.address, &mut self.i2c, &mut self.delay, enabled)
+                    $($await)*
+            }
+
+            /// Query whether automatic self-calibration is enabled
+            ///
+            /// # Errors
+            ///
+            /// Return an error if it cannot communicate with the sensor.
+            pub $($async)* fn get_automatic_self_calibration_enabled(&mut self) -> Result<bool, Error> {
+                debug!("Send command 'get_automatic_self_calibration_enabled'");
+
+                commands::GetAutomaticSelfCalibrationEnabled
+                    .execute(self.address, &mut self.i2c, &mut self.delay, ())
+                    $($await)*
+            }
+
+            /// Start low-power periodic measurement
+            ///
+            /// # Errors
+            ///
+            /// Return an error if it cannot communicate with the sensor.
+            pub $($async)* fn start_low_power_periodic_measurement(
+                mut self,
+            ) -> Result<Scd4x<I2C, D, Measuring>, Error> {
+                debug!("Send command 'start_low_power_periodic_measurement'");
+
+                commands::StartLowPowerPeriodicMeasurement
+                    .execute(self.address, &mut self.i2c, &mut self.delay, ())
+                    $($await)*?;
+
+                Ok(Scd4x {
+                    i2c: self.i2c,
+                    address: self.address,
+                    delay: self.delay,
+                    checksum_failures: self.checksum_failures,
+                    _state: PhantomData,
+                })
+            }
+
+            /// Persist settings to EEPROM
+            ///
+            /// # Errors
+            ///
+            /// Return an error if it cannot communicate with the sensor.
+            pub $($async)* fn persist_settings(&mut self) -> Result<(), Error> {
+                debug!("Send command 'persist_settings'");
+
+                commands::PersistSettings
+                    .execute(self.address, &mut self.i2c, &mut self.delay, ())
+                    $($await)*
+            }
+
+            /// Begin persisting settings to EEPROM
+            ///
+            /// The command is sent without waiting for its completion, and its result
+            /// must be read with [`Scd4x::finish()`] once [`Pending::duration()`] has
+            /// elapsed.
+            ///
+            /// # Errors
+            ///
+            /// Return an error if it cannot communicate with the sensor.
+            pub $($async)* fn begin_persist_settings(&mut self) -> Result<Pending<()>, Error> {
+                debug!("Begin command 'persist_settings'");
+
+                commands::PersistSettings
+                    .begin(self.address, &mut self.i2c, ())
+                    $($await)*
+            }
+
+            /// Obtain the serial number
+            ///
+            /// # Errors
+            ///
+            /// Return an error if it cannot communicate with the sensor.
+            pub $($async)* fn get_serial_number(&mut self) -> Result<u64, Error> {
+                debug!("Send command 'get_serial_number'");
+
+                commands::GetSerialNumber
+                    .execute(self.address, &mut self.i2c, &mut self.delay, ())
+                    $($await)*
+            }
+
+            /// Perform self-test
+            ///
+            /// # Errors
+            ///
+            /// Return an error if it cannot communicate with the sensor.
+            pub $($async)* fn perform_self_test(&mut self) -> Result<bool, Error> {
+                debug!("Send command 'perform_self_test'");
+
+                commands::PerformSelfTest
+                    .execute(self.address, &mut self.i2c, &mut self.delay, ())
+                    $($await)*
+            }
+
+            /// Begin performing self-test
+            ///
+            /// The command is sent without waiting for its completion, and its result
+            /// must be read with [`Scd4x::finish()`] once [`Pending::duration()`] has
+            /// elapsed.
+            ///
+            /// # Errors
+            ///
+            /// Return an error if it cannot communicate with the sensor.
+            pub $($async)* fn begin_perform_self_test(&mut self) -> Result<Pending<bool>, Error> {
+                debug!("Begin command 'perform_self_test'");
+
+                commands::PerformSelfTest
+                    .begin(self.address, &mut self.i2c, ())
+                    $($await)*
+            }
+
+            /// Perform factory reset
+            ///
+            /// # Errors
+            ///
+            /// Return an error if it cannot communicate with the sensor.
+            pub $($async)* fn perform_factory_reset(&mut self) -> Result<(), Error> {
+                debug!("Send command 'perform_factory_reset'");
+
+                commands::PerformFactoryReset
+                    .execute(self.address, &mut self.i2c, &mut self.delay, ())
+                    $($await)*
+            }
+
+            /// Begin performing factory reset
+            ///
+            /// The command is sent without waiting for its completion, and its result
+            /// must be read with [`Scd4x::finish()`] once [`Pending::duration()`] has
+            /// elapsed.
+            ///
+            /// # Errors
+            ///
+            /// Return an error if it cannot communicate with the sensor.
+            pub $($async)* fn begin_perform_factory_reset(&mut self) -> Result<Pending<()>, Error> {
+                debug!("Begin command 'perform_factory_reset'");
+
+                commands::PerformFactoryReset
+                    .begin(self.address, &mut self.i2c, ())
+                    $($await)*
+            }
+
+            /// Reinitialize the sensor
+            ///
+            /// Send a soft-reset signal, obtain the calibration coefficients, and set
+            /// default sampling configuration.
+            ///
+            /// Note that the default sampling configuration disables measurement of
+            /// temperature, pressure and humidity.
+            ///
+            /// # Errors
+            ///
+            /// Return an error if it cannot communicate with the sensor.
+            pub $($async)* fn reinit(&mut self) -> Result<(), Error> {
+                debug!("Send command 'reinit'");
+
+                commands::Reinitialize
+                    .execute(self.address, &mut self.i2c, &mut self.delay, ())
+                    $($await)*
+            }
+
+            /// Begin reinitializing the sensor
+            ///
+            /// The command is sent without waiting for its completion, and its result
+            /// must be read with [`Scd4x::finish()`] once [`Pending::duration()`] has
+            /// elapsed.
+            ///
+            /// # Errors
+            ///
+            /// Return an error if it cannot communicate with the sensor.
+            pub $($async)* fn begin_reinit(&mut self) -> Result<Pending<()>, Error> {
+                debug!("Begin command 'reinit'");
+
+                commands::Reinitialize
+                    .begin(self.address, &mut self.i2c, ())
+                    $($await)*
+            }
+
+            /// Read a single-shot measurement
+            ///
+            /// # Errors
+            ///
+            /// Return an error if it cannot communicate with the sensor.
+            pub $($async)* fn measure_single_shot(mut self) -> Result<Scd4x<I2C, D, Measuring>, Error> {
+                debug!("Send command 'measure_single_shot'");
+
+                commands::MeasureSingleShot
+                    .execute(self.address, &mut self.i2c, &mut self.delay, ())
+                    $($await)*?;
+
+                Ok(Scd4x {
+                    i2c: self.i2c,
+                    address: self.address,
+                    delay: self.delay,
+                    checksum_failures: self.checksum_failures,
+                    _state: PhantomData,
+                })
+            }
+
+            /// Begin reading a single-shot measurement
+            ///
+            /// The command is sent without waiting for its completion, and its result
+            /// must be read with [`Scd4x::finish()`] once [`Pending::duration()`] has
+            /// elapsed.
+            ///
+            /// # Errors
+            ///
+            /// Return an error if it cannot communicate with the sensor.
+            pub $($async)* fn begin_measure_single_shot(
+                mut self,
+            ) -> Result<PendingTransition<I2C, D, Measuring>, Error> {
+                debug!("Begin command 'measure_single_shot'");
+
+                let pending = commands::MeasureSingleShot
+                    .begin(self.address, &mut self.i2c, ())
+                    $($await)*?;
+
+                let sensor = Scd4x {
+                    i2c: self.i2c,
+                    address: self.address,
+                    delay: self.delay,
+                    checksum_failures: self.checksum_failures,
+                    _state: PhantomData,
+                };
+
+                Ok((sensor, pending))
+            }
+
+            /// Read a single-shot measurement of humidity and temperature
+            ///
+            /// # Errors
+            ///
+            /// Return an error if it cannot communicate with the sensor.
+            pub $($async)* fn measure_single_shot_rht_only(
+                mut self,
+            ) -> Result<Scd4x<I2C, D, Measuring>, Error> {
+                debug!("Send command 'measure_single_shot_rht_only'");
+
+                commands::MeasureSingleShotRhtOnly
+                    .execute(self.address, &mut self.i2c, &mut self.delay, ())
+                    $($await)*?;
+
+                Ok(Scd4x {
+                    i2c: self.i2c,
+                    address: self.address,
+                    delay: self.delay,
+                    checksum_failures: self.checksum_failures,
+                    _state: PhantomData,
+                })
+            }
+
+            /// Begin reading a single-shot measurement of humidity and temperature
+            ///
+            /// The command is sent without waiting for its completion, and its result
+            /// must be read with [`Scd4x::finish()`] once [`Pending::duration()`] has
+            /// elapsed.
+            ///
+            /// # Errors
+            ///
+            /// Return an error if it cannot communicate with the sensor.
+            pub $($async)* fn begin_measure_single_shot_rht_only(
+                mut self,
+            ) -> Result<PendingTransition<I2C, D, Measuring>, Error> {
+                debug!("Begin command 'measure_single_shot_rht_only'");
+
+                let pending = commands::MeasureSingleShotRhtOnly
+                    .begin(self.address, &mut self.i2c, ())
+                    $($await)*?;
+
+                let sensor = Scd4x {
+                    i2c: self.i2c,
+                    address: self.address,
+                    delay: self.delay,
+                    checksum_failures: self.checksum_failures,
+                    _state: PhantomData,
+                };
+
+                Ok((sensor, pending))
+            }
+        }
+
+        impl<I2C, D> Scd4x<I2C, D, Measuring>
+        where
+            I2C: I2c,
+            D: DelayNs,
+        {
+            /// Create a new sensor in measuring state using an I²C interface and a
+            /// delay function using the sensor's default address [`DEFAULT_ADDRESS`])
+            pub fn new_in_measuring(i2c: I2C, delay: D) -> Self {
+                Self::new_in_measuring_with_address(i2c, DEFAULT_ADDRESS, delay)
+            }
+
+            /// Create a new sensor in measuring state  using an I²C interface and a
+            /// delay function
+            pub fn new_in_measuring_with_address(i2c: I2C, address: u8, delay: D) -> Self {
+                Self {
+                    i2c,
+                    address,
+                    delay,
+                    checksum_failures: 0,
+                    _state: PhantomData,
+                }
+            }
+
+            /// Read a measurement from the sensor
+            ///
+            /// # Errors
+            ///
+            /// Return an error if it cannot communicate with the sensor.
+            pub $($async)* fn read_measurement(&mut self) -> Result<Sample, Error> {
+                debug!("Send command 'read_measurement'");
+
+                let result = commands::ReadMeasurement
+                    .execute(self.address, &mut self.i2c, &mut self.delay, ())
+                    $($await)*;
+
+                if matches!(result, Err(Error::ChecksumMismatch { .. })) {
+                    self.checksum_failures = self.checksum_failures.saturating_add(1);
+                }
+
+                result
+            }
+
+            /// Read a raw measurement from the sensor
+            ///
+            /// The raw sample can be archived and converted to a [`Sample`] later.
+            ///
+            /// # Errors
+            ///
+            /// Return an error if it cannot communicate with the sensor.
+            pub $($async)* fn read_measurement_raw(&mut self) -> Result<RawSample, Error> {
+                debug!("Send command 'read_measurement_raw'");
+
+                let result = commands::ReadMeasurementRaw
+                    .execute(self.address, &mut self.i2c, &mut self.delay, ())
+                    $($await)*;
+
+                if matches!(result, Err(Error::ChecksumMismatch { .. })) {
+                    self.checksum_failures = self.checksum_failures.saturating_add(1);
+                }
+
+                result
+            }
+
+            #[cfg(feature = "fixed-point")]
+            /// Read a measurement from the sensor in fixed-point representation
+            ///
+            /// The measurement is converted without any floating-point arithmetic.
+            ///
+            /// # Errors
+            ///
+            /// Return an error if it cannot communicate with the sensor.
+            pub $($async)* fn read_measurement_fixed_point(
+                &mut self,
+            ) -> Result<FixedPointSample, Error> {
+                let raw = self.read_measurement_raw()$($await)*?;
+                Ok(FixedPointSample::from(raw))
+            }
+
+            /// Read a measurement from the sensor, validating each field independently
+            ///
+            /// A field whose checksum does not match is returned as `None`, and is
+            /// counted in [`Scd4x::checksum_failures()`].
+            ///
+            /// # Errors
+            ///
+            /// Return an error if it cannot communicate with the sensor.
+            pub $($async)* fn read_measurement_lenient(&mut self) -> Result<PartialSample, Error> {
+                debug!("Send command 'read_measurement_lenient'");
+
+                let sample = commands::ReadMeasurementLenient
+                    .execute(self.address, &mut self.i2c, &mut self.delay, ())
+                    $($await)*?;
+
+                self.checksum_failures = self
+                    .checksum_failures
+                    .saturating_add(sample.invalid_fields());
+
+                Ok(sample)
+            }
+
+            /// Query whether data is available to be read
+            ///
+            /// # Errors
+            ///
+            /// Return an error if it cannot communicate with the sensor.
+            pub $($async)* fn get_data_ready_status(&mut self) -> Result<bool, Error> {
+                debug!("Send command 'get_data_ready_status'");
+
+                commands::GetDataReadyStatus
+                    .execute(self.address, &mut self.i2c, &mut self.delay, ())
+                    $($await)*
+            }
+        }
+
+        impl<I2C, D, S> Scd4x<I2C, D, S>
+        where
+            I2C: I2c,
+            D: DelayNs,
+            S: State,
+        {
+            /// Release the I²C interface
+            pub fn release(self) -> I2C {
+                self.i2c
+            }
+
+            /// Return the number of checksum failures while reading measurements
+            ///
+            /// [`Scd4x::read_measurement()`] and [`Scd4x::read_measurement_raw()`]
+            /// discard the whole sample at the first mismatch and count it as a single
+            /// failure, while
+            /// [`Scd4x::read_measurement_lenient()`] counts every invalid field.
+            /// The counter saturates at [`u32::MAX`].
+            pub fn checksum_failures(&self) -> u32 {
+                self.checksum_failures
+            }
+
+            /// Reset the number of checksum failures to zero
+            pub fn reset_checksum_failures(&mut self) {
+                self.checksum_failures = 0;
+            }
+
+            /// Read the result of a pending command
+            ///
+            /// The pending command must have been returned by one of the `begin_*()`
+            /// functions, and [`Pending::duration()`] must have elapsed since then.
+            ///
+            /// # Errors
+            ///
+            /// Return an error if it cannot communicate with the sensor.
+            pub $($async)* fn finish<T>(&mut self, pending: Pending<T>) -> Result<T, Error> {
+                debug!("Finish pending command");
+
+                finish(self.address, &mut self.i2c, pending)$($await)*
+            }
+
+            /// Stop periodic measurement
+            ///
+            /// # Errors
+            ///
+            /// Return an error if it cannot communicate with the sensor.
+            pub $($async)* fn stop_periodic_measurement(mut self) -> Result<Scd4x<I2C, D, Idle>, Error> {
+                debug!("Send command 'stop_periodic_measurement'");
+
+                commands::StopPeriodicMeasurement
+                    .execute(self.address, &mut self.i2c, &mut self.delay, ())
+                    $($await)*?;
+
+                Ok(Scd4x {
+                    i2c: self.i2c,
+                    address: self.address,
+                    delay: self.delay,
+                    checksum_failures: self.checksum_failures,
+                    _state: PhantomData,
+                })
+            }
+
+            /// Begin stopping periodic measurement
+            ///
+            /// The command is sent without waiting for its completion, and its result
+            /// must be read with [`Scd4x::finish()`] once [`Pending::duration()`] has
+            /// elapsed.
+            ///
+            /// # Errors
+            ///
+            /// Return an error if it cannot communicate with the sensor.
+            pub $($async)* fn begin_stop_periodic_measurement(
+                mut self,
+            ) -> Result<PendingTransition<I2C, D, Idle>, Error> {
+                debug!("Begin command 'stop_periodic_measurement'");
+
+                let pending = commands::StopPeriodicMeasurement
+                    .begin(self.address, &mut self.i2c, ())
+                    $($await)*?;
+
+                let sensor = Scd4x {
+                    i2c: self.i2c,
+                    address: self.address,
+                    delay: self.delay,
+                    checksum_failures: self.checksum_failures,
+                    _state: PhantomData,
+                };
+
+                Ok((sensor, pending))
+            }
+
+            /// Set ambient pressure
+            ///
+            /// # Errors
+            ///
+            /// Return an error if it cannot communicate with the sensor.
+            pub $($async)* fn set_ambient_pressure(
+                &mut self,
+                ambient_pressure: Pressure,
+            ) -> Result<(), Error> {
+                debug!("Send command 'set_ambient_pressure'");
+
+                commands::SetAmbientPressure
+                    .execute(
+                        self.address,
+                        &mut self.i2c,
+                        &mut self.delay,
+                        ambient_pressure,
+                    )
+                    $($await)*
+            }
+
+            /// Get ambient pressure
+            ///
+            /// # Errors
+            ///
+            /// Return an error if it cannot communicate with the sensor.
+            pub $($async)* fn get_ambient_pressure(&mut self) -> Result<Pressure, Error> {
+                debug!("Send command 'get_ambient_pressure'");
+
+                commands::GetAmbientPressure
+                    .execute(self.address, &mut self.i2c, &mut self.delay, ())
+                    $($await)*
+            }
+        }
+
+        #[cfg(test)]
+        mod tests {
+            #![cfg_attr(
+                not(feature = "uom"),
+                expect(clippy::float_cmp, reason = "Exact values are expected")
+            )]
+
+            use super::*;
+
+            use core::time::Duration;
+
+            use embedded_hal_mock::eh1::delay::NoopDelay as DelayMock;
+            use embedded_hal_mock::eh1::i2c::Mock as I2cMock;
+            use embedded_hal_mock::eh1::i2c::Transaction as I2cTransaction;
+
+            use $crate::sample::altitude_from_meter;
+            use $crate::sample::co2_from_ppm;
+            use $crate::sample::humidity_from_number;
+            use $crate::sample::pressure_from_hectopascal;
+            use $crate::sample::temperature_from_celsius;
+            use $crate::Error;
+
+            #[$($test)*]
+            $($async)* fn test_get_serial_number() -> Result<(), Error> {
+                let expectations = [
+                    I2cTransaction::write(DEFAULT_ADDRESS, vec![0x36, 0x82]),
+                    I2cTransaction::read(
+                        DEFAULT_ADDRESS,
+                        vec![0xf8, 0x96, 0x31, 0x9f, 0x07, 0xc2, 0x3b, 0xbe, 0x89],
+                    ),
+                ];
+                let i2c = I2cMock::new(&expectations);
+
+                let mut scd4x = Scd4x::new(i2c, DelayMock);
+
+                let serial_number = scd4x.get_serial_number()$($await)*?;
+
+                assert_eq!(serial_number, 273_325_796_834_238);
+
+                scd4x.release().done();
+                Ok(())
+            }
+
+            #[$($test)*]
+            $($async)* fn test_reinit() -> Result<(), Error> {
+                let expectations = [I2cTransaction::write(DEFAULT_ADDRESS, vec![0x36, 0x46])];
+                let i2c = I2cMock::new(&expectations);
+
+                let mut scd4x = Scd4x::new(i2c, DelayMock);
+
+                scd4x.reinit()$($await)*?;
+
+                scd4x.release().done();
+                Ok(())
+            }
+
+            #[$($test)*]
+            $($async)* fn test_measure_single_shot() -> Result<(), Error> {
+                let expectations = [I2cTransaction::write(DEFAULT_ADDRESS, vec![0x21, 0x9d])];
+                let i2c = I2cMock::new(&expectations);
+
+                let scd4x = Scd4x::new(i2c, DelayMock);
+
+                let scd4x = scd4x.measure_single_shot()$($await)*?;
+
+                scd4x.release().done();
+                Ok(())
+            }
+
+            #[$($test)*]
+            $($async)* fn test_begin_measure_single_shot() -> Result<(), Error> {
+                let expectations = [I2cTransaction::write(DEFAULT_ADDRESS, vec![0x21, 0x9d])];
+                let i2c = I2cMock::new(&expectations);
+
+                let scd4x = Scd4x::new(i2c, DelayMock);
+
+                let (mut scd4x, pending) = scd4x.begin_measure_single_shot()$($await)*?;
+                assert_eq!(pending.duration(), Duration::from_secs(5));
+
+                scd4x.finish(pending)$($await)*?;
+
+                scd4x.release().done();
+                Ok(())
+            }
+
+            #[$($test)*]
+            $($async)* fn test_measure_single_shot_rht_only() -> Result<(), Error> {
+                let expectations = [I2cTransaction::write(DEFAULT_ADDRESS, vec![0x21, 0x96])];
+                let i2c = I2cMock::new(&expectations);
+
+                let scd4x = Scd4x::new(i2c, DelayMock);
+
+                let scd4x = scd4x.measure_single_shot_rht_only()$($await)*?;
+
+                scd4x.release().done();
+                Ok(())
+            }
+
+            #[$($test)*]
+            $($async)* fn test_perform_factory_reset() -> Result<(), Error> {
+                let expectations = [I2cTransaction::write(DEFAULT_ADDRESS, vec![0x36, 0x32])];
+                let i2c = I2cMock::new(&expectations);
+
+                let mut scd4x = Scd4x::new(i2c, DelayMock);
+
+                scd4x.perform_factory_reset()$($await)*?;
+
+                scd4x.release().done();
+                Ok(())
+            }
+
+            #[$($test)*]
+            $($async)* fn test_perform_self_test() -> Result<(), Error> {
+                let expectations = [
+                    I2cTransaction::write(DEFAULT_ADDRESS, vec![0x36, 0x39]),
+                    I2cTransaction::read(DEFAULT_ADDRESS, vec![0x00, 0x00, 0x81]),
+                ];
+                let i2c = I2cMock::new(&expectations);
+
+                let mut scd4x = Scd4x::new(i2c, DelayMock);
+
+                let result = scd4x.perform_self_test()$($await)*?;
+                assert!(result);
+
+                scd4x.release().done();
+                Ok(())
+            }
+
+            #[$($test)*]
+            $($async)* fn test_begin_perform_self_test() -> Result<(), Error> {
+                let expectations = [
+                    I2cTransaction::write(DEFAULT_ADDRESS, vec![0x36, 0x39]),
+                    I2cTransaction::read(DEFAULT_ADDRESS, vec![0x00, 0x00, 0x81]),
+                ];
+                let i2c = I2cMock::new(&expectations);
+
+                let mut scd4x = Scd4x::new(i2c, DelayMock);
+
+                let pending = scd4x.begin_perform_self_test()$($await)*?;
+                assert_eq!(pending.duration(), Duration::from_secs(10));
+
+                let result = scd4x.finish(pending)$($await)*?;
+                assert!(result);
+
+                scd4x.release().done();
+                Ok(())
+            }
+
+            #[$($test)*]
+            $($async)* fn test_persist_settings() -> Result<(), Error> {
+                let expectations = [I2cTransaction::write(DEFAULT_ADDRESS, vec![0x36, 0x15])];
+                let i2c = I2cMock::new(&expectations);
+
+                let mut scd4x = Scd4x::new(i2c, DelayMock);
+
+                scd4x.persist_settings()$($await)*?;
+
+                scd4x.release().done();
+                Ok(())
+            }
+
+            #[$($test)*]
+            $($async)* fn test_get_data_ready_status() -> Result<(), Error> {
+                let expectations = [
+                    I2cTransaction::write(DEFAULT_ADDRESS, vec![0xe4, 0xb8]),
+                    I2cTransaction::read(DEFAULT_ADDRESS, vec![0x80, 0x00, 0xa2]),
+                ];
+                let i2c = I2cMock::new(&expectations);
+
+                let mut scd4x = Scd4x::new_in_measuring(i2c, DelayMock);
+
+                let ready = scd4x.get_data_ready_status()$($await)*?;
+                assert!(!ready);
+
+                scd4x.release().done();
+                Ok(())
+            }
+
+            #[$($test)*]
+            $($async)* fn test_start_low_power_periodic_measurement() -> Result<(), Error> {
+                let expectations = [I2cTransaction::write(DEFAULT_ADDRESS, vec![0x21, 0xac])];
+                let i2c = I2cMock::new(&expectations);
+
+                let scd4x = Scd4x::new(i2c, DelayMock);
+
+                let scd4x = scd4x.start_low_power_periodic_measurement()$($await)*?;
+
+                scd4x.release().done();
+                Ok(())
+            }
+
+            #[$($test)*]
+            $($async)* fn test_get_automatic_self_calibration_enabled() -> Result<(), Error> {
+                let expectations = [
+                    I2cTransaction::write(DEFAULT_ADDRESS, vec![0x23, 0x13]),
+                    I2cTransaction::read(DEFAULT_ADDRESS, vec![0x00, 0x00, 0x81]),
+                ];
+                let i2c = I2cMock::new(&expectations);
+
+                let mut scd4x = Scd4x::new(i2c, DelayMock);
+
+                let enabled = scd4x.get_automatic_self_calibration_enabled()$($await)*?;
+                assert!(!enabled);
+
+                scd4x.release().done();
+                Ok(())
+            }
+
+            #[$($test)*]
+            $($async)* fn test_set_automatic_self_calibration_enabled() -> Result<(), Error> {
+                let expectations = [I2cTransaction::write(
+                    DEFAULT_ADDRESS,
+                    vec![0x24, 0x16, 0x00, 0x01, 0xb0],
+                )];
+                let i2c = I2cMock::new(&expectations);
+
+                let mut scd4x = Scd4x::new(i2c, DelayMock);
+
+                scd4x.set_automatic_self_calibration_enabled(true)$($await)*?;
+
+                scd4x.release().done();
+                Ok(())
+            }
+
+            #[$($test)*]
+            $($async)* fn test_perform_forced_recalibration() -> Result<(), Error> {
+                let expectations = [
+                    I2cTransaction::write(DEFAULT_ADDRESS, vec![0x36, 0x2f, 0x01, 0xe0, 0xb4]),
+                    I2cTransaction::read(DEFAULT_ADDRESS, vec![0x7f, 0xce, 0x7b]),
+                ];
+                let i2c = I2cMock::new(&expectations);
+
+                let mut scd4x = Scd4x::new(i2c, DelayMock);
+
+                let correction = scd4x
+                    .perform_forced_recalibration(co2_from_ppm(480.0))
+                    $($await)*?;
+                assert_eq!(correction, Some(co2_from_ppm(-50.0)));
+
+                scd4x.release().done();
+                Ok(())
+            }
+
+            #[$($test)*]
+            $($async)* fn test_begin_perform_forced_recalibration() -> Result<(), Error> {
+                let expectations = [
+                    I2cTransaction::write(DEFAULT_ADDRESS, vec![0x36, 0x2f, 0x01, 0xe0, 0xb4]),
+                    I2cTransaction::read(DEFAULT_ADDRESS, vec![0x7f, 0xce, 0x7b]),
+                ];
+                let i2c = I2cMock::new(&expectations);
+
+                let mut scd4x = Scd4x::new(i2c, DelayMock);
+
+                let pending = scd4x
+                    .begin_perform_forced_recalibration(co2_from_ppm(480.0))
+                    $($await)*?;
+                let correction = scd4x.finish(pending)$($await)*?;
+                assert_eq!(correction, Some(co2_from_ppm(-50.0)));
+
+                scd4x.release().done();
+                Ok(())
+            }
+
+            #[$($test)*]
+            $($async)* fn test_perform_forced_recalibration_failure() -> Result<(), Error> {
+                let expectations = [
+                    I2cTransaction::write(DEFAULT_ADDRESS, vec![0x36, 0x2f, 0x01, 0xe0, 0xb4]),
+                    I2cTransaction::read(DEFAULT_ADDRESS, vec![0xff, 0xff, 0xac]),
+                ];
+                let i2c = I2cMock::new(&expectations);
+
+                let mut scd4x = Scd4x::new(i2c, DelayMock);
+
+                let correction = scd4x
+                    .perform_forced_recalibration(co2_from_ppm(480.0))
+                    $($await)*?;
+                assert_eq!(correction, None);
+
+                scd4x.release().done();
+                Ok(())
+            }
+
+            #[$($test)*]
+            $($async)* fn test_set_ambient_pressure() -> Result<(), Error> {
+                let expectations = [I2cTransaction::write(
+                    DEFAULT_ADDRESS,
+                    vec![0xe0, 0x00, 0x03, 0xdb, 0x42],
+                )];
+                let i2c = I2cMock::new(&expectations);
+
+                let mut scd4x = Scd4x::new(i2c, DelayMock);
+
+                scd4x
+                    .set_ambient_pressure(pressure_from_hectopascal(987.0))
+                    $($await)*?;
+
+                scd4x.release().done();
+                Ok(())
+            }
+
+            #[$($test)*]
+            $($async)* fn test_get_ambient_pressure() -> Result<(), Error> {
+                let expectations = [
+                    I2cTransaction::write(DEFAULT_ADDRESS, vec![0xe0, 0x00]),
+                    I2cTransaction::read(DEFAULT_ADDRESS, vec![0x03, 0xdb, 0x42]),
+                ];
+                let i2c = I2cMock::new(&expectations);
+
+                let mut scd4x = Scd4x::new(i2c, DelayMock);
+
+                let ambient_pressure = scd4x.get_ambient_pressure()$($await)*?;
+                assert_eq!(ambient_pressure, pressure_from_hectopascal(987.0));
+
+                scd4x.release().done();
+                Ok(())
+            }
+
+            #[$($test)*]
+            $($async)* fn test_get_sensor_altitude() -> Result<(), Error> {
+                let expectations = [
+                    I2cTransaction::write(DEFAULT_ADDRESS, vec![0x23, 0x22]),
+                    I2cTransaction::read(DEFAULT_ADDRESS, vec![0x04, 0x4c, 0x42]),
+                ];
+                let i2c = I2cMock::new(&expectations);
+
+                let mut scd4x = Scd4x::new(i2c, DelayMock);
+
+                let sensor_altitude = scd4x.get_sensor_altitude()$($await)*?;
+                assert_eq!(sensor_altitude, altitude_from_meter(1100.0));
+
+                scd4x.release().done();
+                Ok(())
+            }
+
+            #[$($test)*]
+            $($async)* fn test_check_ambient_pressure() -> Result<(), Error> {
+                let expectations = [
+                    I2cTransaction::write(DEFAULT_ADDRESS, vec![0x23, 0x22]),
+                    I2cTransaction::read(DEFAULT_ADDRESS, vec![0x04, 0x4c, 0x42]),
+                    I2cTransaction::write(DEFAULT_ADDRESS, vec![0x23, 0x22]),
+                    I2cTransaction::read(DEFAULT_ADDRESS, vec![0x04, 0x4c, 0x42]),
+                ];
+                let i2c = I2cMock::new(&expectations);
+
+                let mut scd4x = Scd4x::new(i2c, DelayMock);
+
+                let tolerance = pressure_from_hectopascal(50.0);
+
+                let consistent = scd4x
+                    .check_ambient_pressure(pressure_from_hectopascal(870.0), tolerance)
+                    $($await)*?;
+                assert!(consistent);
+
+                let consistent = scd4x
+                    .check_ambient_pressure(pressure_from_hectopascal(1013.0), tolerance)
+                    $($await)*?;
+                assert!(!consistent);
+
+                scd4x.release().done();
+                Ok(())
+            }
+
+            #[$($test)*]
+            $($async)* fn test_set_sensor_altitude() -> Result<(), Error> {
+                let expectations = [I2cTransaction::write(
+                    DEFAULT_ADDRESS,
+                    vec![0x24, 0x27, 0x07, 0x9e, 0x09],
+                )];
+                let i2c = I2cMock::new(&expectations);
+
+                let mut scd4x = Scd4x::new(i2c, DelayMock);
+
+                scd4x
+                    .set_sensor_altitude(altitude_from_meter(1950.0))
+                    $($await)*?;
+
+                scd4x.release().done();
+                Ok(())
+            }
+
+            #[$($test)*]
+            $($async)* fn test_get_temperature_offset() -> Result<(), Error> {
+                let expectations = [
+                    I2cTransaction::write(DEFAULT_ADDRESS, vec![0x23, 0x18]),
+                    I2cTransaction::read(DEFAULT_ADDRESS, vec![0x09, 0x12, 0x63]),
+                ];
+                let i2c = I2cMock::new(&expectations);
+
+                let mut scd4x = Scd4x::new(i2c, DelayMock);
+
+                let temperature_offset = scd4x.get_temperature_offset()$($await)*?;
+                assert_eq!(temperature_offset, temperature_from_celsius(6.200_409));
+
+                scd4x.release().done();
+                Ok(())
+            }
+
+            #[$($test)*]
+            $($async)* fn test_set_temperature_offset() -> Result<(), Error> {
+                let expectations = [I2cTransaction::write(
+                    DEFAULT_ADDRESS,
+                    vec![0x24, 0x1d, 0x07, 0xe6, 0x48],
+                )];
+                let i2c = I2cMock::new(&expectations);
+
+                let mut scd4x = Scd4x::new(i2c, DelayMock);
+
+                scd4x
+                    .set_temperature_offset(temperature_from_celsius(5.4))
+                    $($await)*?;
+
+                scd4x.release().done();
+                Ok(())
+            }
+
+            #[$($test)*]
+            $($async)* fn test_stop_periodic_measurement() -> Result<(), Error> {
+                let expectations = [I2cTransaction::write(DEFAULT_ADDRESS, vec![0x3f, 0x86])];
+                let i2c = I2cMock::new(&expectations);
+
+                let scd4x = Scd4x::new(i2c, DelayMock);
+
+                let scd4x = scd4x.stop_periodic_measurement()$($await)*?;
+
+                scd4x.release().done();
+                Ok(())
+            }
+
+            #[$($test)*]
+            $($async)* fn test_read_measurement() -> Result<(), Error> {
+                let expectations = [
+                    I2cTransaction::write(DEFAULT_ADDRESS, vec![0xec, 0x05]),
+                    I2cTransaction::read(
+                        DEFAULT_ADDRESS,
+                        vec![0x01, 0xf4, 0x33, 0x66, 0x67, 0xa2, 0x5e, 0xb9, 0x3c],
+                    ),
+                ];
+                let i2c = I2cMock::new(&expectations);
+
+                let mut scd4x = Scd4x::new_in_measuring(i2c, DelayMock);
+
+                let sample = scd4x.read_measurement()$($await)*?;
+                let expected = Sample {
+                    co2: co2_from_ppm(500.0),
+                    temperature: temperature_from_celsius(25.001_602),
+                    humidity: humidity_from_number(37.001_038),
+                };
+
+                assert_eq!(sample, expected);
+
+                scd4x.release().done();
+                Ok(())
+            }
+
+            #[$($test)*]
+            $($async)* fn test_read_measurement_raw() -> Result<(), Error> {
+                let expectations = [
+                    I2cTransaction::write(DEFAULT_ADDRESS, vec![0xec, 0x05]),
+                    I2cTransaction::read(
+                        DEFAULT_ADDRESS,
+                        vec![0x01, 0xf4, 0x33, 0x66, 0x67, 0xa2, 0x5e, 0xb9, 0x3c],
+                    ),
+                ];
+                let i2c = I2cMock::new(&expectations);
+
+                let mut scd4x = Scd4x::new_in_measuring(i2c, DelayMock);
+
+                let raw = scd4x.read_measurement_raw()$($await)*?;
+                let expected = RawSample {
+                    co2: 0x01f4,
+                    temperature: 0x6667,
+                    humidity: 0x5eb9,
+                };
+
+                assert_eq!(raw, expected);
+
+                let sample = Sample::from(raw);
+                let expected = Sample {
+                    co2: co2_from_ppm(500.0),
+                    temperature: temperature_from_celsius(25.001_602),
+                    humidity: humidity_from_number(37.001_038),
+                };
+
+                assert_eq!(sample, expected);
+
+                scd4x.release().done();
+                Ok(())
+            }
+
+            #[cfg(feature = "fixed-point")]
+            #[$($test)*]
+            $($async)* fn test_read_measurement_fixed_point() -> Result<(), Error> {
+                let expectations = [
+                    I2cTransaction::write(DEFAULT_ADDRESS, vec![0xec, 0x05]),
+                    I2cTransaction::read(
+                        DEFAULT_ADDRESS,
+                        vec![0x01, 0xf4, 0x33, 0x66, 0x67, 0xa2, 0x5e, 0xb9, 0x3c],
+                    ),
+                ];
+                let i2c = I2cMock::new(&expectations);
+
+                let mut scd4x = Scd4x::new_in_measuring(i2c, DelayMock);
+
+                let sample = scd4x.read_measurement_fixed_point()$($await)*?;
+                let expected = FixedPointSample {
+                    co2: 500,
+                    temperature: 25_002_i32,
+                    humidity: 37_001,
+                };
+
+                assert_eq!(sample, expected);
+
+                scd4x.release().done();
+                Ok(())
+            }
+
+            #[$($test)*]
+            $($async)* fn test_read_measurement_checksum_mismatch() -> Result<(), Error> {
+                let expectations = [
+                    I2cTransaction::write(DEFAULT_ADDRESS, vec![0xec, 0x05]),
+                    I2cTransaction::read(
+                        DEFAULT_ADDRESS,
+                        vec![0x01, 0xf4, 0x33, 0x66, 0x67, 0xa3, 0x5e, 0xb9, 0x3d],
+                    ),
+                ];
+                let i2c = I2cMock::new(&expectations);
+
+                let mut scd4x = Scd4x::new_in_measuring(i2c, DelayMock);
+
+                let result = scd4x.read_measurement()$($await)*;
+                let expected = Err(Error::ChecksumMismatch {
+                    actual: 0xa2,
+                    expected: 0xa3,
+                });
+
+                assert_eq!(result, expected);
+                assert_eq!(scd4x.checksum_failures(), 1);
+
+                scd4x.release().done();
+                Ok(())
+            }
+
+            #[$($test)*]
+            $($async)* fn test_read_measurement_lenient() -> Result<(), Error> {
+                let expectations = [
+                    I2cTransaction::write(DEFAULT_ADDRESS, vec![0xec, 0x05]),
+                    I2cTransaction::read(
+                        DEFAULT_ADDRESS,
+                        vec![0x01, 0xf4, 0x33, 0x66, 0x67, 0xa3, 0x5e, 0xb9, 0x3d],
+                    ),
+                    I2cTransaction::write(DEFAULT_ADDRESS, vec![0xec, 0x05]),
+                    I2cTransaction::read(
+                        DEFAULT_ADDRESS,
+                        vec![0x01, 0xf4, 0x33, 0x66, 0x67, 0xa2, 0x5e, 0xb9, 0x3c],
+                    ),
+                ];
+                let i2c = I2cMock::new(&expectations);
+
+                let mut scd4x = Scd4x::new_in_measuring(i2c, DelayMock);
+
+                let sample = scd4x.read_measurement_lenient()$($await)*?;
+                let expected = PartialSample {
+                    co2: Some(co2_from_ppm(500.0)),
+                    temperature: None,
+                    humidity: None,
+                };
+
+                assert_eq!(sample, expected);
+                assert_eq!(sample.into_sample(), None);
+                assert_eq!(scd4x.checksum_failures(), 2);
+
+                let sample = scd4x.read_measurement_lenient()$($await)*?;
+                let expected = Sample {
+                    co2: co2_from_ppm(500.0),
+                    temperature: temperature_from_celsius(25.001_602),
+                    humidity: humidity_from_number(37.001_038),
+                };
+
+                assert_eq!(sample.into_sample(), Some(expected));
+                assert_eq!(scd4x.checksum_failures(), 2);
+
+                scd4x.reset_checksum_failures();
+                assert_eq!(scd4x.checksum_failures(), 0);
+
+                scd4x.release().done();
+                Ok(())
+            }
+
+            #[$($test)*]
+            $($async)* fn test_start_periodic_measurement() -> Result<(), Error> {
+                let expectations = [I2cTransaction::write(DEFAULT_ADDRESS, vec![0x21, 0xb1])];
+                let i2c = I2cMock::new(&expectations);
+
+                let scd4x = Scd4x::new(i2c, DelayMock);
+
+                let scd4x = scd4x.start_periodic_measurement()$($await)*?;
+
+                scd4x.release().done();
+
+                Ok(())
+            }
+        }
+
+        #[cfg(all(test, feature = "sim"))]
+        mod timing_tests {
+            use super::*;
+
+            use core::time::Duration;
+
+            use $crate::datasheet::execution_time;
+            use $crate::protocol::Command;
+            use $crate::sample::altitude_from_meter;
+            use $crate::sample::co2_from_ppm;
+            use $crate::sample::pressure_from_hectopascal;
+            use $crate::sample::temperature_from_celsius;
+            use $crate::sim::Simulator;
+            use $crate::sim::VirtualClock;
+
+            /// Interval between periodic measurements
+            const PERIODIC_INTERVAL: Duration = Duration::from_secs(5);
+
+            /// Assert that the driver delayed at least the datasheet execution
+            /// time of a command, and forget the delays
+            fn assert_delayed(clock: &VirtualClock, command: &impl Command) {
+                let register = command.register();
+                let expected = execution_time(register);
+                let actual = clock.total_delay();
+
+                assert!(
+                    expected.is_some_and(|expected| actual >= expected),
+                    "{register:#06x}: {actual:?} < {expected:?}"
+                );
+
+                clock.clear_delays();
+            }
+
+            #[$($test)*]
+            $($async)* fn test_idle_delays() -> Result<(), Error> {
+                let clock = VirtualClock::new();
+                let mut simulator = Simulator::new(&clock, Sample::default());
+                let mut scd4x = Scd4x::new(&mut simulator, &clock);
+
+                let _serial_number = scd4x.get_serial_number()$($await)*?;
+                assert_delayed(&clock, &commands::GetSerialNumber);
+
+                scd4x.set_temperature_offset(temperature_from_celsius(4.0))$($await)*?;
+                assert_delayed(&clock, &commands::SetTemperatureOffset);
+
+                let _temperature_offset = scd4x.get_temperature_offset()$($await)*?;
+                assert_delayed(&clock, &commands::GetTemperatureOffset);
+
+                scd4x.set_sensor_altitude(altitude_from_meter(1100.0))$($await)*?;
+                assert_delayed(&clock, &commands::SetSensorAltitude);
+
+                let _altitude = scd4x.get_sensor_altitude()$($await)*?;
+                assert_delayed(&clock, &commands::GetSensorAltitude);
+
+                let pressure = pressure_from_hectopascal(887.0);
+                let tolerance = pressure_from_hectopascal(50.0);
+                let _consistent = scd4x.check_ambient_pressure(pressure, tolerance)$($await)*?;
+                assert_delayed(&clock, &commands::GetSensorAltitude);
+
+                scd4x.set_ambient_pressure(pressure)$($await)*?;
+                assert_delayed(&clock, &commands::SetAmbientPressure);
+
+                let _pressure = scd4x.get_ambient_pressure()$($await)*?;
+                assert_delayed(&clock, &commands::GetAmbientPressure);
+
+                scd4x.set_automatic_self_calibration_enabled(false)$($await)*?;
+                assert_delayed(&clock, &commands::SetAutomaticSelfCalibrationEnabled);
+
+                let _enabled = scd4x.get_automatic_self_calibration_enabled()$($await)*?;
+                assert_delayed(&clock, &commands::GetAutomaticSelfCalibrationEnabled);
+
+                scd4x.persist_settings()$($await)*?;
+                assert_delayed(&clock, &commands::PersistSettings);
+
+                let _correction = scd4x.perform_forced_recalibration(co2_from_ppm(400.0))$($await)*?;
+                assert_delayed(&clock, &commands::PerformForcedRecalibration);
+
+                let _passed = scd4x.perform_self_test()$($await)*?;
+                assert_delayed(&clock, &commands::PerformSelfTest);
+
+                scd4x.reinit()$($await)*?;
+                assert_delayed(&clock, &commands::Reinitialize);
+
+                scd4x.perform_factory_reset()$($await)*?;
+                assert_delayed(&clock, &commands::PerformFactoryReset);
+
+                Ok(())
+            }
+
+            #[$($test)*]
+            $($async)* fn test_measuring_delays() -> Result<(), Error> {
+                let clock = VirtualClock::new();
+                let mut simulator = Simulator::new(&clock, Sample::default());
+                let scd4x = Scd4x::new(&mut simulator, &clock);
+
+                let mut scd4x = scd4x.start_periodic_measurement()$($await)*?;
+                assert_delayed(&clock, &commands::StartPeriodicMeasurement);
+
+                clock.advance(PERIODIC_INTERVAL);
+                let _ready = scd4x.get_data_ready_status()$($await)*?;
+                assert_delayed(&clock, &commands::GetDataReadyStatus);
+
+                let _sample = scd4x.read_measurement()$($await)*?;
+                assert_delayed(&clock, &commands::ReadMeasurement);
+
+                clock.advance(PERIODIC_INTERVAL);
+                let _sample = scd4x.read_measurement_raw()$($await)*?;
+                assert_delayed(&clock, &commands::ReadMeasurementRaw);
+
+                clock.advance(PERIODIC_INTERVAL);
+                let _sample = scd4x.read_measurement_lenient()$($await)*?;
+                assert_delayed(&clock, &commands::ReadMeasurementLenient);
+
+                #[cfg(feature = "fixed-point")]
+                {
+                    clock.advance(PERIODIC_INTERVAL);
+                    let _sample = scd4x.read_measurement_fixed_point()$($await)*?;
+                    assert_delayed(&clock, &commands::ReadMeasurement);
+                }
+
+                scd4x.set_ambient_pressure(pressure_from_hectopascal(987.0))$($await)*?;
+                assert_delayed(&clock, &commands::SetAmbientPressure);
+
+                let scd4x = scd4x.stop_periodic_measurement()$($await)*?;
+                assert_delayed(&clock, &commands::StopPeriodicMeasurement);
+
+                let scd4x = scd4x.start_low_power_periodic_measurement()$($await)*?;
+                assert_delayed(&clock, &commands::StartLowPowerPeriodicMeasurement);
+
+                let _scd4x = scd4x.stop_periodic_measurement()$($await)*?;
+                assert_delayed(&clock, &commands::StopPeriodicMeasurement);
+
+                Ok(())
+            }
+
+            #[$($test)*]
+            $($async)* fn test_single_shot_delays() -> Result<(), Error> {
+                let clock = VirtualClock::new();
+                let mut simulator = Simulator::new(&clock, Sample::default());
+                let scd4x = Scd4x::new(&mut simulator, &clock);
+
+                let mut scd4x = scd4x.measure_single_shot()$($await)*?;
+                assert_delayed(&clock, &commands::MeasureSingleShot);
+
+                let _sample = scd4x.read_measurement()$($await)*?;
+                assert_delayed(&clock, &commands::ReadMeasurement);
+
+                let scd4x = scd4x.stop_periodic_measurement()$($await)*?;
+                assert_delayed(&clock, &commands::StopPeriodicMeasurement);
+
+                let mut scd4x = scd4x.measure_single_shot_rht_only()$($await)*?;
+                assert_delayed(&clock, &commands::MeasureSingleShotRhtOnly);
+
+                let _sample = scd4x.read_measurement()$($await)*?;
+                assert_delayed(&clock, &commands::ReadMeasurement);
+
+                Ok(())
+            }
+        }
+    };
+}
+
+pub(crate) use define_sensor;
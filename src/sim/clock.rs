@@ -0,0 +1,243 @@
+// Copyright Claudio Mattera 2024-2026.
+//
+// Distributed under the MIT License or the Apache 2.0 License at your option.
+// See the accompanying files LICENSE-MIT.txt and LICENSE-APACHE-2.0.txt, or
+// online at
+// https://opensource.org/licenses/MIT
+// https://opensource.org/licenses/Apache-2.0
+
+//! Data types and functions for virtual time
+//!
+//! A [`VirtualClock`] is shared between the sensor and the simulator.
+//! The sensor uses it as a delay provider, and every delay advances the
+//! simulated time instantly, so commands such as self-tests take no real time.
+//! The simulator uses it as a [`Clock`], and it does not acknowledge reads
+//! before a command has been executed, so drivers that do not wait long
+//! enough fail.
+
+use core::cell::Cell;
+use core::time::Duration;
+
+use embedded_hal::delay::DelayNs;
+
+use super::Clock;
+
+/// A clock advanced by delays instead of real time
+///
+/// Delays are implemented for shared references, so the same clock can be
+/// passed both to the sensor and to the simulator.
+/// Each call to a delay function is recorded as a single delay, even when it
+/// exceeds the range of nanoseconds representable in a `u32`.
+///
+/// ~~~~
+/// use core::time::Duration;
+///
+/// use embedded_hal::delay::DelayNs;
+///
+/// use scd4x_rs::sim::VirtualClock;
+///
+/// let clock = VirtualClock::new();
+///
+/// (&clock).delay_ms(1200);
+/// (&clock).delay_ms(5000);
+///
+/// assert_eq!(clock.elapsed(), Duration::from_millis(6200));
+/// assert_eq!(clock.delay_count(), 2);
+/// assert_eq!(clock.last_delay(), Some(Duration::from_secs(5)));
+/// assert_eq!(clock.longest_delay(), Some(Duration::from_secs(5)));
+/// ~~~~
+#[derive(Debug, Default)]
+pub struct VirtualClock {
+    /// Current time
+    now: Cell<Duration>,
+
+    /// Number of requested delays
+    delay_count: Cell<u32>,
+
+    /// Total time spent in requested delays
+    total_delay: Cell<Duration>,
+
+    /// Last requested delay
+    last_delay: Cell<Option<Duration>>,
+
+    /// Longest requested delay
+    longest_delay: Cell<Option<Duration>>,
+}
+
+impl VirtualClock {
+    /// Create a new clock at time zero
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the time elapsed since the creation of the clock
+    pub fn elapsed(&self) -> Duration {
+        self.now.get()
+    }
+
+    /// Advance the time without recording a delay
+    pub fn advance(&self, duration: Duration) {
+        self.now.set(self.now.get() + duration);
+    }
+
+    /// Advance the time to an instant, unless it is already past it
+    pub fn advance_to(&self, instant: Duration) {
+        if instant > self.now.get() {
+            self.now.set(instant);
+        }
+    }
+
+    /// Return the number of requested delays
+    pub fn delay_count(&self) -> u32 {
+        self.delay_count.get()
+    }
+
+    /// Return the total time spent in requested delays
+    pub fn total_delay(&self) -> Duration {
+        self.total_delay.get()
+    }
+
+    /// Return the last requested delay
+    pub fn last_delay(&self) -> Option<Duration> {
+        self.last_delay.get()
+    }
+
+    /// Return the longest requested delay
+    pub fn longest_delay(&self) -> Option<Duration> {
+        self.longest_delay.get()
+    }
+
+    /// Forget the requested delays, without changing the time
+    pub fn clear_delays(&self) {
+        self.delay_count.set(0);
+        self.total_delay.set(Duration::ZERO);
+        self.last_delay.set(None);
+        self.longest_delay.set(None);
+    }
+
+    /// Record a delay and advance the time
+    fn delay(&self, duration: Duration) {
+        self.advance(duration);
+
+        self.delay_count
+            .set(self.delay_count.get().saturating_add(1));
+        self.total_delay.set(self.total_delay.get() + duration);
+        self.last_delay.set(Some(duration));
+
+        let longest = match self.longest_delay.get() {
+            Some(longest) => longest.max(duration),
+            None => duration,
+        };
+        self.longest_delay.set(Some(longest));
+    }
+}
+
+impl Clock for &VirtualClock {
+    fn now(&self) -> Duration {
+        self.now.get()
+    }
+}
+
+impl DelayNs for &VirtualClock {
+    fn delay_ns(&mut self, ns: u32) {
+        self.delay(Duration::from_nanos(u64::from(ns)));
+    }
+
+    fn delay_us(&mut self, us: u32) {
+        self.delay(Duration::from_micros(u64::from(us)));
+    }
+
+    fn delay_ms(&mut self, ms: u32) {
+        self.delay(Duration::from_millis(u64::from(ms)));
+    }
+}
+
+#[cfg(feature = "async")]
+impl embedded_hal_async::delay::DelayNs for &VirtualClock {
+    async fn delay_ns(&mut self, ns: u32) {
+        self.delay(Duration::from_nanos(u64::from(ns)));
+    }
+
+    async fn delay_us(&mut self, us: u32) {
+        self.delay(Duration::from_micros(u64::from(us)));
+    }
+
+    async fn delay_ms(&mut self, ms: u32) {
+        self.delay(Duration::from_millis(u64::from(ms)));
+    }
+}
+
+#[cfg(all(test, feature = "blocking"))]
+mod tests {
+    #![expect(clippy::panic_in_result_fn, reason = "Assertions in tests")]
+
+    use super::*;
+
+    use embedded_hal::i2c::ErrorKind;
+    use embedded_hal::i2c::NoAcknowledgeSource;
+    use embedded_hal_mock::eh1::delay::NoopDelay;
+
+    use crate::sim::Simulator;
+    use crate::Error;
+    use crate::Sample;
+    use crate::Scd4x;
+
+    #[test]
+    fn test_delays() {
+        let clock = VirtualClock::new();
+
+        (&clock).delay_us(500);
+        (&clock).delay_ms(10);
+        clock.advance(Duration::from_secs(1));
+        (&clock).delay_ns(1);
+
+        assert_eq!(clock.elapsed(), Duration::from_nanos(1_010_500_001));
+        assert_eq!(clock.delay_count(), 3);
+        assert_eq!(clock.total_delay(), Duration::from_nanos(10_500_001));
+        assert_eq!(clock.last_delay(), Some(Duration::from_nanos(1)));
+        assert_eq!(clock.longest_delay(), Some(Duration::from_millis(10)));
+
+        clock.advance_to(Duration::from_secs(1));
+        assert_eq!(clock.elapsed(), Duration::from_nanos(1_010_500_001));
+        clock.advance_to(Duration::from_secs(2));
+        assert_eq!(clock.elapsed(), Duration::from_secs(2));
+
+        clock.clear_delays();
+        assert_eq!(clock.delay_count(), 0);
+        assert_eq!(clock.total_delay(), Duration::ZERO);
+        assert_eq!(clock.last_delay(), None);
+        assert_eq!(clock.longest_delay(), None);
+        assert_eq!(clock.elapsed(), Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_self_test_is_instant() -> Result<(), Error> {
+        let clock = VirtualClock::new();
+        let mut simulator = Simulator::new(&clock, Sample::default());
+
+        let mut scd4x = Scd4x::new(&mut simulator, &clock);
+        assert!(scd4x.perform_self_test()?);
+
+        assert_eq!(clock.longest_delay(), Some(Duration::from_secs(10)));
+        assert!(clock.elapsed() >= Duration::from_secs(10));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_missing_delay_is_detected() {
+        let clock = VirtualClock::new();
+        let mut simulator = Simulator::new(&clock, Sample::default());
+
+        let mut scd4x = Scd4x::new(&mut simulator, NoopDelay);
+
+        let result = scd4x.perform_self_test();
+        assert_eq!(
+            result,
+            Err(Error::I2c(ErrorKind::NoAcknowledge(
+                NoAcknowledgeSource::Address
+            )))
+        );
+    }
+}
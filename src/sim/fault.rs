@@ -0,0 +1,445 @@
+// Copyright Claudio Mattera 2024-2026.
+//
+// Distributed under the MIT License or the Apache 2.0 License at your option.
+// See the accompanying files LICENSE-MIT.txt and LICENSE-APACHE-2.0.txt, or
+// online at
+// https://opensource.org/licenses/MIT
+// https://opensource.org/licenses/Apache-2.0
+
+//! Data types and functions for injecting faults on an I²C bus
+//!
+//! A [`FaultInjector`] wraps an I²C device, usually a
+//! [`Simulator`](super::Simulator), and it makes it misbehave according to a
+//! [`Schedule`].
+//! Faults surface through the same paths as on a real bus, i.e. as
+//! `Error::I2c` and `Error::ChecksumMismatch` in the sensor.
+
+use embedded_hal::i2c::Error as _;
+use embedded_hal::i2c::ErrorKind;
+use embedded_hal::i2c::ErrorType;
+use embedded_hal::i2c::I2c;
+use embedded_hal::i2c::NoAcknowledgeSource;
+use embedded_hal::i2c::Operation;
+use embedded_hal::i2c::SevenBitAddress;
+
+use crate::checksum::compute as compute_checksum;
+
+/// Register of the command for querying whether data is available
+const GET_DATA_READY_STATUS: u16 = 0xe4b8;
+
+/// Register of the command for performing a self-test
+const PERFORM_SELF_TEST: u16 = 0x3639;
+
+/// Word returned by a stuck data-ready status
+const DATA_NOT_READY: u16 = 0x8000;
+
+/// A fault on the I²C bus
+///
+/// Checksum corruptions, missing acknowledgements and arbitration losses only
+/// affect the transaction they are scheduled on.
+/// Stuck data-ready flags and self-test failures persist until
+/// [`FaultInjector::clear_faults()`] is called.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Fault {
+    /// Corrupt the last checksum byte of every read in the transaction
+    CorruptChecksum,
+
+    /// Do not acknowledge the address
+    Nack,
+
+    /// Lose arbitration on the bus
+    ArbitrationLoss,
+
+    /// Report that no data is available, regardless of the device
+    StuckDataReady,
+
+    /// Report a self-test failure with the given word
+    SelfTestFailure(u16),
+}
+
+/// A schedule of faults
+pub trait Schedule {
+    /// Return the fault to inject in a transaction, if any
+    ///
+    /// Transactions are numbered from zero.
+    fn fault(&mut self, transaction: u32) -> Option<Fault>;
+}
+
+impl<F> Schedule for F
+where
+    F: FnMut(u32) -> Option<Fault>,
+{
+    fn fault(&mut self, transaction: u32) -> Option<Fault> {
+        self(transaction)
+    }
+}
+
+/// A schedule of faults at fixed transactions
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FixedSchedule<'faults> {
+    /// Pairs of transaction number and fault
+    faults: &'faults [(u32, Fault)],
+}
+
+impl<'faults> FixedSchedule<'faults> {
+    /// Create a new schedule from pairs of transaction number and fault
+    #[must_use]
+    pub fn new(faults: &'faults [(u32, Fault)]) -> Self {
+        Self { faults }
+    }
+}
+
+impl Schedule for FixedSchedule<'_> {
+    fn fault(&mut self, transaction: u32) -> Option<Fault> {
+        self.faults
+            .iter()
+            .find(|&&(number, _)| number == transaction)
+            .map(|&(_, fault)| fault)
+    }
+}
+
+/// A schedule of faults chosen by a seedable pseudo-random generator
+///
+/// The same seed always produces the same sequence of faults.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RandomSchedule<'faults> {
+    /// State of the xorshift generator
+    state: u32,
+
+    /// Inverse of the probability of a fault in each transaction
+    one_in: u32,
+
+    /// Faults to choose from
+    faults: &'faults [Fault],
+}
+
+impl<'faults> RandomSchedule<'faults> {
+    /// Create a new schedule
+    ///
+    /// Each transaction has a probability of `1 / one_in` of a fault, chosen
+    /// uniformly from `faults`.
+    #[must_use]
+    pub fn new(seed: u32, one_in: u32, faults: &'faults [Fault]) -> Self {
+        Self {
+            // The xorshift generator is stuck at zero
+            state: if seed == 0 { 0x9e37_79b9 } else { seed },
+            one_in: one_in.max(1),
+            faults,
+        }
+    }
+
+    /// Return the next pseudo-random number
+    fn next_random(&mut self) -> u32 {
+        let mut state = self.state;
+        state ^= state << 13_u32;
+        state ^= state >> 17_u32;
+        state ^= state << 5_u32;
+        self.state = state;
+        state
+    }
+}
+
+impl Schedule for RandomSchedule<'_> {
+    fn fault(&mut self, _transaction: u32) -> Option<Fault> {
+        let draw = self.next_random();
+        let choice = self.next_random();
+
+        if draw % self.one_in != 0 {
+            return None;
+        }
+
+        let index = usize::try_from(choice).ok()? % self.faults.len().max(1);
+        self.faults.get(index).copied()
+    }
+}
+
+/// An I²C device wrapper injecting faults according to a schedule
+pub struct FaultInjector<I2C, S> {
+    /// Wrapped I²C device
+    i2c: I2C,
+
+    /// Schedule of faults
+    schedule: S,
+
+    /// Number of transactions so far
+    transactions: u32,
+
+    /// Register of the last command written to the device
+    last_register: Option<u16>,
+
+    /// Whether the data-ready flag is stuck
+    stuck_data_ready: bool,
+
+    /// Word reported by failing self-tests
+    self_test_failure: Option<u16>,
+}
+
+impl<I2C, S> FaultInjector<I2C, S>
+where
+    S: Schedule,
+{
+    /// Create a new fault injector
+    pub fn new(i2c: I2C, schedule: S) -> Self {
+        Self {
+            i2c,
+            schedule,
+            transactions: 0,
+            last_register: None,
+            stuck_data_ready: false,
+            self_test_failure: None,
+        }
+    }
+
+    /// Release the wrapped I²C device
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+
+    /// Return the number of transactions so far
+    pub fn transactions(&self) -> u32 {
+        self.transactions
+    }
+
+    /// Clear stuck data-ready flags and self-test failures
+    pub fn clear_faults(&mut self) {
+        self.stuck_data_ready = false;
+        self.self_test_failure = None;
+    }
+
+    /// Prepare a transaction, and return the fault to inject in it
+    ///
+    /// # Errors
+    ///
+    /// Return an error if the transaction must fail before reaching the
+    /// device.
+    fn before(&mut self, operations: &[Operation<'_>]) -> Result<Option<Fault>, ErrorKind> {
+        let fault = self.schedule.fault(self.transactions);
+        self.transactions = self.transactions.wrapping_add(1);
+
+        match fault {
+            Some(Fault::Nack) => {
+                return Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address));
+            }
+            Some(Fault::ArbitrationLoss) => return Err(ErrorKind::ArbitrationLoss),
+            Some(Fault::StuckDataReady) => self.stuck_data_ready = true,
+            Some(Fault::SelfTestFailure(word)) => self.self_test_failure = Some(word),
+            Some(Fault::CorruptChecksum) | None => {}
+        }
+
+        for operation in operations {
+            if let Operation::Write(&[high, low, ..]) = *operation {
+                self.last_register = Some(u16::from_be_bytes([high, low]));
+            }
+        }
+
+        Ok(fault)
+    }
+
+    /// Alter the data read in a transaction
+    fn after(&self, fault: Option<Fault>, operations: &mut [Operation<'_>]) {
+        let replacement = match self.last_register {
+            Some(GET_DATA_READY_STATUS) if self.stuck_data_ready => Some(DATA_NOT_READY),
+            Some(PERFORM_SELF_TEST) => self.self_test_failure,
+            _ => None,
+        };
+
+        for operation in operations {
+            if let Operation::Read(ref mut buffer) = *operation {
+                if let (Some(word), &mut [ref mut msb, ref mut lsb, ref mut checksum, ..]) =
+                    (replacement, &mut **buffer)
+                {
+                    [*msb, *lsb] = word.to_be_bytes();
+                    *checksum = compute_checksum(&[*msb, *lsb]);
+                }
+
+                if fault == Some(Fault::CorruptChecksum) {
+                    if let Some(last) = buffer.last_mut() {
+                        *last ^= 0xff;
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<I2C, S> ErrorType for FaultInjector<I2C, S> {
+    type Error = ErrorKind;
+}
+
+impl<I2C, S> I2c for FaultInjector<I2C, S>
+where
+    I2C: I2c,
+    S: Schedule,
+{
+    fn transaction(
+        &mut self,
+        address: SevenBitAddress,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        let fault = self.before(operations)?;
+        self.i2c
+            .transaction(address, operations)
+            .map_err(|error| error.kind())?;
+        self.after(fault, operations);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<I2C, S> embedded_hal_async::i2c::I2c for FaultInjector<I2C, S>
+where
+    I2C: embedded_hal_async::i2c::I2c,
+    S: Schedule,
+{
+    async fn transaction(
+        &mut self,
+        address: SevenBitAddress,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        let fault = self.before(operations)?;
+        self.i2c
+            .transaction(address, operations)
+            .await
+            .map_err(|error| error.kind())?;
+        self.after(fault, operations);
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "blocking"))]
+mod tests {
+    #![expect(clippy::panic_in_result_fn, reason = "Assertions in tests")]
+
+    use super::*;
+
+    use core::time::Duration;
+
+    use crate::sim::Simulator;
+    use crate::sim::VirtualClock;
+    use crate::Error;
+    use crate::Sample;
+    use crate::Scd4x;
+
+    #[test]
+    fn test_corrupt_checksum() -> Result<(), Error> {
+        let clock = VirtualClock::new();
+        let simulator = Simulator::new(&clock, Sample::default());
+        let faults = [(1, Fault::CorruptChecksum)];
+        let injector = FaultInjector::new(simulator, FixedSchedule::new(&faults));
+
+        let mut scd4x = Scd4x::new(injector, &clock);
+
+        let result = scd4x.get_serial_number();
+        assert!(matches!(result, Err(Error::ChecksumMismatch { .. })));
+
+        let serial_number = scd4x.get_serial_number()?;
+        assert_eq!(serial_number, 0xf896_9f07_3bbe);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_nack_on_nth_transaction() -> Result<(), Error> {
+        let clock = VirtualClock::new();
+        let simulator = Simulator::new(&clock, Sample::default());
+        let injector = FaultInjector::new(simulator, |transaction| {
+            (transaction == 4).then_some(Fault::Nack)
+        });
+
+        let mut scd4x = Scd4x::new(injector, &clock);
+
+        let _temperature_offset = scd4x.get_temperature_offset()?;
+        let _sensor_altitude = scd4x.get_sensor_altitude()?;
+
+        let result = scd4x.get_serial_number();
+        assert_eq!(
+            result,
+            Err(Error::I2c(ErrorKind::NoAcknowledge(
+                NoAcknowledgeSource::Address
+            )))
+        );
+
+        assert_eq!(scd4x.release().transactions(), 5);
+        Ok(())
+    }
+
+    #[test]
+    fn test_arbitration_loss() {
+        let clock = VirtualClock::new();
+        let simulator = Simulator::new(&clock, Sample::default());
+        let injector = FaultInjector::new(simulator, |_| Some(Fault::ArbitrationLoss));
+
+        let mut scd4x = Scd4x::new(injector, &clock);
+
+        let result = scd4x.get_serial_number();
+        assert_eq!(result, Err(Error::I2c(ErrorKind::ArbitrationLoss)));
+    }
+
+    #[test]
+    fn test_stuck_data_ready() -> Result<(), Error> {
+        let clock = VirtualClock::new();
+        let simulator = Simulator::new(&clock, Sample::default());
+        let faults = [(2, Fault::StuckDataReady)];
+        let injector = FaultInjector::new(simulator, FixedSchedule::new(&faults));
+
+        let scd4x = Scd4x::new(injector, &clock);
+        let mut scd4x = scd4x.start_periodic_measurement()?;
+
+        clock.advance_to(Duration::from_secs(60));
+        assert!(!scd4x.get_data_ready_status()?);
+        assert!(!scd4x.get_data_ready_status()?);
+
+        let scd4x = scd4x.stop_periodic_measurement()?;
+        let mut injector = scd4x.release();
+        injector.clear_faults();
+
+        let scd4x = Scd4x::new(injector, &clock);
+        let mut scd4x = scd4x.start_periodic_measurement()?;
+        clock.advance_to(Duration::from_secs(120));
+        assert!(scd4x.get_data_ready_status()?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_self_test_failure() -> Result<(), Error> {
+        let clock = VirtualClock::new();
+        let simulator = Simulator::new(&clock, Sample::default());
+        let faults = [(0, Fault::SelfTestFailure(0x0001))];
+        let injector = FaultInjector::new(simulator, FixedSchedule::new(&faults));
+
+        let mut scd4x = Scd4x::new(injector, &clock);
+
+        assert!(!scd4x.perform_self_test()?);
+        assert!(!scd4x.perform_self_test()?);
+
+        let mut injector = scd4x.release();
+        injector.clear_faults();
+
+        let mut scd4x = Scd4x::new(injector, &clock);
+        assert!(scd4x.perform_self_test()?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_random_schedule_is_reproducible() {
+        let faults = [Fault::Nack, Fault::CorruptChecksum, Fault::ArbitrationLoss];
+
+        let mut first = RandomSchedule::new(42, 4, &faults);
+        let mut second = RandomSchedule::new(42, 4, &faults);
+        let mut third = RandomSchedule::new(43, 4, &faults);
+
+        let mut injected = 0;
+        let mut different = false;
+        for transaction in 0..1000 {
+            let fault = first.fault(transaction);
+            assert_eq!(fault, second.fault(transaction));
+            different |= fault != third.fault(transaction);
+            injected += u32::from(fault.is_some());
+        }
+
+        assert!(different);
+        assert!((150..350).contains(&injected), "{injected}");
+    }
+}
@@ -0,0 +1,869 @@
+// Copyright Claudio Mattera 2024-2026.
+//
+// Distributed under the MIT License or the Apache 2.0 License at your option.
+// See the accompanying files LICENSE-MIT.txt and LICENSE-APACHE-2.0.txt, or
+// online at
+// https://opensource.org/licenses/MIT
+// https://opensource.org/licenses/Apache-2.0
+
+//! Simulated SCD4x sensor for testing without hardware
+//!
+//! A [`Simulator`] behaves like a SCD4x sensor attached to an I²C bus.
+//! It implements the I²C trait of `embedded-hal`, and also the one of
+//! `embedded-hal-async` when the feature `async` is enabled, so it can be
+//! passed to both the blocking and the asynchronous sensors.
+//!
+//! The simulator decodes every command, validates checksums and responds with
+//! CRC-correct data.
+//! It follows the same rules as the real sensor:
+//!
+//! *   It does not acknowledge its address while a command is being executed.
+//! *   It does not acknowledge commands that are not allowed during periodic
+//!     measurements.
+//! *   It does not acknowledge reads when no data is available.
+//! *   It keeps volatile and persisted settings, and it restores the latter
+//!     when reinitialized or power-cycled.
+//!
+//! Time is provided by a [`Clock`], and measurements are scripted by an
+//! [`Environment`].
+//! Both are implemented for closures.
+//! A [`VirtualClock`] is both a clock for the simulator and a delay provider
+//! for the sensor, so long commands complete instantly while the simulator
+//! still rejects reads issued before the execution time has elapsed.
+//!
+//! A [`FaultInjector`] wraps the simulator, or any other I²C device, and
+//! injects faults such as corrupted checksums, missing acknowledgements and
+//! arbitration losses according to a seedable [`Schedule`].
+//!
+//! ~~~~
+//! use core::cell::Cell;
+//! use core::time::Duration;
+//!
+//! use embedded_hal::i2c::I2c;
+//!
+//! use scd4x_rs::protocol::Command;
+//! use scd4x_rs::protocol::GetSerialNumber;
+//! use scd4x_rs::sim::Simulator;
+//! use scd4x_rs::Sample;
+//! use scd4x_rs::DEFAULT_ADDRESS;
+//!
+//! let now = Cell::new(Duration::ZERO);
+//! let mut simulator = Simulator::new(|| now.get(), Sample::default());
+//!
+//! simulator.write(DEFAULT_ADDRESS, GetSerialNumber.encode(()).as_ref())?;
+//! now.set(now.get() + GetSerialNumber.execution_time());
+//!
+//! let mut response = [0; 9];
+//! simulator.read(DEFAULT_ADDRESS, &mut response)?;
+//!
+//! let serial_number = GetSerialNumber.decode(response)?;
+//! assert_eq!(serial_number, simulator.serial_number());
+//! # Ok::<(), scd4x_rs::Error>(())
+//! ~~~~
+
+use core::time::Duration;
+
+use embedded_hal::i2c::ErrorKind;
+use embedded_hal::i2c::ErrorType;
+use embedded_hal::i2c::I2c;
+use embedded_hal::i2c::NoAcknowledgeSource;
+use embedded_hal::i2c::Operation;
+use embedded_hal::i2c::SevenBitAddress;
+
+use crate::calibration::recompensate;
+use crate::checksum::compute as compute_checksum;
+use crate::conversion::co2_to_word;
+use crate::conversion::humidity_to_word;
+use crate::conversion::temperature_to_word;
+use crate::conversion::word_to_temperature_offset;
+use crate::sample::co2_from_ppm;
+use crate::sample::ppm_from_co2;
+use crate::Sample;
+use crate::DEFAULT_ADDRESS;
+
+mod clock;
+pub use self::clock::VirtualClock;
+
+mod fault;
+pub use self::fault::Fault;
+pub use self::fault::FaultInjector;
+pub use self::fault::FixedSchedule;
+pub use self::fault::RandomSchedule;
+pub use self::fault::Schedule;
+
+/// Serial number of a new simulator
+const DEFAULT_SERIAL_NUMBER: u64 = 0xf896_9f07_3bbe;
+
+/// Factory temperature offset, 4 °C
+const DEFAULT_TEMPERATURE_OFFSET: u16 = 0x05da;
+
+/// Factory sensor altitude, 0 m
+const DEFAULT_SENSOR_ALTITUDE: u16 = 0x0000;
+
+/// Default ambient pressure after power-up, 1013 hPa
+const DEFAULT_AMBIENT_PRESSURE: u16 = 0x03f5;
+
+/// Time required by the sensor to power up
+const POWER_UP_TIME: Duration = Duration::from_secs(1);
+
+/// Interval between periodic measurements
+const PERIODIC_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Interval between low-power periodic measurements
+const LOW_POWER_PERIODIC_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Registers of the commands allowed during periodic measurements
+const ALLOWED_WHILE_MEASURING: [u16; 4] = [0xec05, 0xe4b8, 0x3f86, 0xe000];
+
+/// A source of simulated time
+pub trait Clock {
+    /// Return the time elapsed since an arbitrary, fixed origin
+    fn now(&self) -> Duration;
+}
+
+impl<F> Clock for F
+where
+    F: Fn() -> Duration,
+{
+    fn now(&self) -> Duration {
+        self()
+    }
+}
+
+/// A script of the conditions measured by the simulated sensor
+///
+/// Samples are expressed as they would be reported by the sensor with factory
+/// settings.
+/// The simulator adjusts them for the configured temperature offset and for
+/// forced recalibrations.
+pub trait Environment {
+    /// Return the sample measured at a time
+    fn sample(&mut self, time: Duration) -> Sample;
+}
+
+impl<F> Environment for F
+where
+    F: FnMut(Duration) -> Sample,
+{
+    fn sample(&mut self, time: Duration) -> Sample {
+        self(time)
+    }
+}
+
+impl Environment for Sample {
+    fn sample(&mut self, _time: Duration) -> Sample {
+        *self
+    }
+}
+
+/// Measurement mode of the simulated sensor
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Mode {
+    /// The sensor is idle
+    Idle,
+
+    /// The sensor is measuring periodically
+    Periodic {
+        /// Interval between measurements
+        interval: Duration,
+
+        /// Time of the next measurement
+        next: Duration,
+    },
+}
+
+/// Settings of the simulated sensor, stored as words
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Settings {
+    /// Temperature offset
+    temperature_offset: u16,
+
+    /// Sensor altitude
+    sensor_altitude: u16,
+
+    /// Whether automatic self-calibration is enabled
+    automatic_self_calibration: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            temperature_offset: DEFAULT_TEMPERATURE_OFFSET,
+            sensor_altitude: DEFAULT_SENSOR_ALTITUDE,
+            automatic_self_calibration: true,
+        }
+    }
+}
+
+/// Words to be read from the simulated sensor
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Output {
+    /// Words
+    words: [u16; 3],
+
+    /// Number of valid words
+    length: usize,
+}
+
+impl Output {
+    /// Create an output with a single word
+    fn word(word: u16) -> Self {
+        Self {
+            words: [word, 0, 0],
+            length: 1,
+        }
+    }
+
+    /// Create an output with three words
+    fn three_words(words: [u16; 3]) -> Self {
+        Self { words, length: 3 }
+    }
+}
+
+/// A simulated SCD4x sensor
+pub struct Simulator<C, E> {
+    /// I²C address
+    address: u8,
+
+    /// Source of simulated time
+    clock: C,
+
+    /// Script of the measured conditions
+    environment: E,
+
+    /// Serial number
+    serial_number: u64,
+
+    /// Measurement mode
+    mode: Mode,
+
+    /// Time until which the sensor is executing a command
+    busy_until: Duration,
+
+    /// Volatile settings
+    settings: Settings,
+
+    /// Settings persisted to EEPROM
+    persisted_settings: Settings,
+
+    /// Ambient pressure
+    ambient_pressure: u16,
+
+    /// Correction to CO₂ concentration from forced recalibration, in ppm
+    co2_correction: i16,
+
+    /// Last measured CO₂ concentration, in ppm
+    last_co2: Option<u16>,
+
+    /// Last measurement not yet read
+    measurement: Option<[u16; 3]>,
+
+    /// Words to be read
+    output: Option<Output>,
+}
+
+impl<C, E> Simulator<C, E>
+where
+    C: Clock,
+    E: Environment,
+{
+    /// Create a new simulator at the default address
+    pub fn new(clock: C, environment: E) -> Self {
+        Self::new_with_address(clock, DEFAULT_ADDRESS, environment)
+    }
+
+    /// Create a new simulator at a custom address
+    pub fn new_with_address(clock: C, address: u8, environment: E) -> Self {
+        Self {
+            address,
+            clock,
+            environment,
+            serial_number: DEFAULT_SERIAL_NUMBER,
+            mode: Mode::Idle,
+            busy_until: Duration::ZERO,
+            settings: Settings::default(),
+            persisted_settings: Settings::default(),
+            ambient_pressure: DEFAULT_AMBIENT_PRESSURE,
+            co2_correction: 0,
+            last_co2: None,
+            measurement: None,
+            output: None,
+        }
+    }
+
+    /// Return the serial number
+    pub fn serial_number(&self) -> u64 {
+        self.serial_number
+    }
+
+    /// Set the serial number
+    ///
+    /// Only the lowest 48 bits are reported by the sensor.
+    pub fn set_serial_number(&mut self, serial_number: u64) {
+        self.serial_number = serial_number;
+    }
+
+    /// Return whether the sensor is measuring periodically
+    pub fn is_measuring(&self) -> bool {
+        matches!(self.mode, Mode::Periodic { .. })
+    }
+
+    /// Simulate a power cycle
+    ///
+    /// The sensor stops measuring, restores the persisted settings and resets
+    /// the ambient pressure.
+    /// It does not acknowledge its address until it has powered up.
+    pub fn power_cycle(&mut self) {
+        self.mode = Mode::Idle;
+        self.busy_until = self.clock.now() + POWER_UP_TIME;
+        self.settings = self.persisted_settings;
+        self.ambient_pressure = DEFAULT_AMBIENT_PRESSURE;
+        self.measurement = None;
+        self.output = None;
+    }
+
+    /// Process a sequence of I²C operations
+    fn process(
+        &mut self,
+        address: SevenBitAddress,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), ErrorKind> {
+        let now = self.clock.now();
+
+        if address != self.address || now < self.busy_until {
+            return Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address));
+        }
+
+        self.update(now);
+
+        for operation in operations {
+            match *operation {
+                Operation::Write(bytes) => self.write_bytes(now, bytes)?,
+                Operation::Read(ref mut buffer) => self.read_bytes(buffer)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Perform the periodic measurements due before a time
+    fn update(&mut self, now: Duration) {
+        if let Mode::Periodic { interval, mut next } = self.mode {
+            let mut latest = None;
+            while next <= now {
+                latest = Some(next);
+                next += interval;
+            }
+
+            self.mode = Mode::Periodic { interval, next };
+
+            if let Some(time) = latest {
+                self.measurement = Some(self.measure(time, true));
+            }
+        }
+    }
+
+    /// Measure the environment at a time
+    fn measure(&mut self, time: Duration, with_co2: bool) -> [u16; 3] {
+        let sample = self.environment.sample(time);
+
+        let sample = recompensate(
+            &sample,
+            word_to_temperature_offset(DEFAULT_TEMPERATURE_OFFSET),
+            word_to_temperature_offset(self.settings.temperature_offset),
+        );
+
+        let co2 = if with_co2 {
+            let ppm = ppm_from_co2(sample.co2) + f32::from(self.co2_correction);
+            let co2 = co2_to_word(co2_from_ppm(ppm));
+            self.last_co2 = Some(co2);
+            co2
+        } else {
+            0
+        };
+
+        [
+            co2,
+            temperature_to_word(sample.temperature),
+            humidity_to_word(sample.humidity),
+        ]
+    }
+
+    /// Decode and execute a command written to the sensor
+    fn write_bytes(&mut self, now: Duration, bytes: &[u8]) -> Result<(), ErrorKind> {
+        let (register, argument) = match *bytes {
+            [high, low] => (u16::from_be_bytes([high, low]), None),
+            [high, low, msb, lsb, checksum] if compute_checksum(&[msb, lsb]) == checksum => (
+                u16::from_be_bytes([high, low]),
+                Some(u16::from_be_bytes([msb, lsb])),
+            ),
+            _ => return Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data)),
+        };
+
+        if self.is_measuring() && !ALLOWED_WHILE_MEASURING.contains(&register) {
+            return Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data));
+        }
+
+        self.output = None;
+
+        let execution_time = self.execute(now, register, argument)?;
+        self.busy_until = now + execution_time;
+
+        Ok(())
+    }
+
+    /// Execute a command, and return its execution time
+    fn execute(
+        &mut self,
+        now: Duration,
+        register: u16,
+        argument: Option<u16>,
+    ) -> Result<Duration, ErrorKind> {
+        let execution_time = match (register, argument) {
+            (0x21b1, None) => {
+                self.mode = Mode::Periodic {
+                    interval: PERIODIC_INTERVAL,
+                    next: now + PERIODIC_INTERVAL,
+                };
+                Duration::ZERO
+            }
+            (0x21ac, None) => {
+                self.mode = Mode::Periodic {
+                    interval: LOW_POWER_PERIODIC_INTERVAL,
+                    next: now + LOW_POWER_PERIODIC_INTERVAL,
+                };
+                Duration::ZERO
+            }
+            (0x3f86, None) => {
+                self.mode = Mode::Idle;
+                Duration::from_millis(500)
+            }
+            (0xec05, None) => {
+                self.output = self.measurement.take().map(Output::three_words);
+                Duration::from_millis(1)
+            }
+            (0xe4b8, None) => {
+                let word = if self.measurement.is_some() {
+                    0x8006
+                } else {
+                    0x8000
+                };
+                self.output = Some(Output::word(word));
+                Duration::from_millis(1)
+            }
+            (0x3682, None) => {
+                let [_, _, word0_high, word0_low, word1_high, word1_low, word2_high, word2_low] =
+                    self.serial_number.to_be_bytes();
+                self.output = Some(Output::three_words([
+                    u16::from_be_bytes([word0_high, word0_low]),
+                    u16::from_be_bytes([word1_high, word1_low]),
+                    u16::from_be_bytes([word2_high, word2_low]),
+                ]));
+                Duration::from_millis(1)
+            }
+            (0x3639, None) => {
+                self.output = Some(Output::word(0x0000));
+                Duration::from_secs(10)
+            }
+            (0x219d, None) => {
+                let execution_time = Duration::from_secs(5);
+                self.measurement = Some(self.measure(now + execution_time, true));
+                execution_time
+            }
+            (0x2196, None) => {
+                let execution_time = Duration::from_millis(50);
+                self.measurement = Some(self.measure(now + execution_time, false));
+                execution_time
+            }
+            _ => self.execute_setting(register, argument)?,
+        };
+
+        Ok(execution_time)
+    }
+
+    /// Execute a command on settings or calibration, and return its execution
+    /// time
+    fn execute_setting(
+        &mut self,
+        register: u16,
+        argument: Option<u16>,
+    ) -> Result<Duration, ErrorKind> {
+        let execution_time = match (register, argument) {
+            (0x2318, None) => {
+                self.output = Some(Output::word(self.settings.temperature_offset));
+                Duration::from_millis(1)
+            }
+            (0x241d, Some(word)) => {
+                self.settings.temperature_offset = word;
+                Duration::from_millis(1)
+            }
+            (0x2322, None) => {
+                self.output = Some(Output::word(self.settings.sensor_altitude));
+                Duration::from_millis(1)
+            }
+            (0x2427, Some(word)) => {
+                self.settings.sensor_altitude = word;
+                Duration::from_millis(1)
+            }
+            (0xe000, None) => {
+                self.output = Some(Output::word(self.ambient_pressure));
+                Duration::from_millis(1)
+            }
+            (0xe000, Some(word)) => {
+                self.ambient_pressure = word;
+                Duration::from_millis(1)
+            }
+            (0x362f, Some(reference)) => {
+                let word = self.perform_forced_recalibration(reference);
+                self.output = Some(Output::word(word));
+                Duration::from_millis(400)
+            }
+            (0x2313, None) => {
+                let word = u16::from(self.settings.automatic_self_calibration);
+                self.output = Some(Output::word(word));
+                Duration::from_millis(1)
+            }
+            (0x2416, Some(word)) => {
+                self.settings.automatic_self_calibration = word != 0;
+                Duration::from_millis(1)
+            }
+            (0x3615, None) => {
+                self.persisted_settings = self.settings;
+                Duration::from_millis(800)
+            }
+            (0x3632, None) => {
+                self.settings = Settings::default();
+                self.persisted_settings = Settings::default();
+                self.co2_correction = 0;
+                self.last_co2 = None;
+                Duration::from_millis(1200)
+            }
+            (0x3646, None) => {
+                self.settings = self.persisted_settings;
+                Duration::from_millis(30)
+            }
+            _ => return Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data)),
+        };
+
+        Ok(execution_time)
+    }
+
+    /// Perform a forced recalibration, and return the correction word
+    ///
+    /// The correction is `0xffff` if no CO₂ concentration was measured before.
+    fn perform_forced_recalibration(&mut self, reference: u16) -> u16 {
+        let Some(co2) = self.last_co2 else {
+            return 0xffff;
+        };
+
+        let correction = i16::try_from(i32::from(reference) - i32::from(co2)).unwrap_or(i16::MAX);
+        self.co2_correction = self.co2_correction.saturating_add(correction);
+
+        #[expect(clippy::cast_sign_loss, reason = "Wrapping is desired")]
+        let word = (correction as u16).wrapping_add(0x8000);
+
+        word
+    }
+
+    /// Read the pending output from the sensor
+    ///
+    /// Bytes beyond the pending output are read as `0xff`, as on a real bus
+    /// where no device drives the data line.
+    fn read_bytes(&mut self, buffer: &mut [u8]) -> Result<(), ErrorKind> {
+        let output = self
+            .output
+            .take()
+            .ok_or(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address))?;
+
+        buffer.fill(0xff);
+
+        for (chunk, word) in buffer.chunks_mut(3).zip(&output.words[..output.length]) {
+            let [msb, lsb] = word.to_be_bytes();
+            let bytes = [msb, lsb, compute_checksum(&[msb, lsb])];
+            for (byte, value) in chunk.iter_mut().zip(bytes) {
+                *byte = value;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<C, E> ErrorType for Simulator<C, E> {
+    type Error = ErrorKind;
+}
+
+impl<C, E> I2c for Simulator<C, E>
+where
+    C: Clock,
+    E: Environment,
+{
+    fn transaction(
+        &mut self,
+        address: SevenBitAddress,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.process(address, operations)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<C, E> embedded_hal_async::i2c::I2c for Simulator<C, E>
+where
+    C: Clock,
+    E: Environment,
+{
+    async fn transaction(
+        &mut self,
+        address: SevenBitAddress,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.process(address, operations)
+    }
+}
+
+#[cfg(all(test, feature = "blocking"))]
+mod tests {
+    #![expect(clippy::panic_in_result_fn, reason = "Assertions in tests")]
+    #![cfg_attr(
+        not(feature = "uom"),
+        expect(clippy::float_cmp, reason = "Exact values are expected")
+    )]
+
+    use super::*;
+
+    use crate::sample::altitude_from_meter;
+    use crate::sample::celsius_from_temperature;
+    use crate::sample::humidity_from_number;
+    use crate::sample::number_from_humidity;
+    use crate::sample::temperature_from_celsius;
+    use crate::Error;
+    use crate::Scd4x;
+
+    /// A CO₂ concentration rising by 1 ppm per second from 600 ppm
+    fn rising_co2(time: Duration) -> Sample {
+        Sample {
+            co2: co2_from_ppm(600.0 + time.as_secs_f32()),
+            temperature: temperature_from_celsius(21.0),
+            humidity: humidity_from_number(40.0),
+        }
+    }
+
+    /// Return a sample as rounded raw values
+    fn rounded(sample: &Sample) -> (f32, f32, f32) {
+        (
+            ppm_from_co2(sample.co2),
+            (celsius_from_temperature(sample.temperature) * 10.0).round() / 10.0,
+            (number_from_humidity(sample.humidity) * 10.0).round() / 10.0,
+        )
+    }
+
+    #[test]
+    fn test_serial_number() -> Result<(), Error> {
+        let clock = VirtualClock::new();
+        let mut simulator = Simulator::new(&clock, rising_co2);
+        simulator.set_serial_number(0x0123_4567_89ab);
+
+        let mut scd4x = Scd4x::new(&mut simulator, &clock);
+        let serial_number = scd4x.get_serial_number()?;
+
+        assert_eq!(serial_number, 0x0123_4567_89ab);
+        Ok(())
+    }
+
+    #[test]
+    fn test_periodic_measurement() -> Result<(), Error> {
+        let clock = VirtualClock::new();
+        let mut simulator = Simulator::new(&clock, rising_co2);
+
+        let scd4x = Scd4x::new(&mut simulator, &clock);
+        let mut scd4x = scd4x.start_periodic_measurement()?;
+
+        assert!(!scd4x.get_data_ready_status()?);
+        let result = scd4x.read_measurement();
+        assert_eq!(
+            result,
+            Err(Error::I2c(ErrorKind::NoAcknowledge(
+                NoAcknowledgeSource::Address
+            )))
+        );
+
+        clock.advance_to(Duration::from_secs(12));
+        assert!(scd4x.get_data_ready_status()?);
+        let sample = scd4x.read_measurement()?;
+        assert_eq!(rounded(&sample), (610.0, 21.0, 40.0));
+        assert!(!scd4x.get_data_ready_status()?);
+
+        let _scd4x = scd4x.stop_periodic_measurement()?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_commands_rejected_while_measuring() -> Result<(), Error> {
+        let clock = VirtualClock::new();
+        let mut simulator = Simulator::new(&clock, rising_co2);
+
+        {
+            let scd4x = Scd4x::new(&mut simulator, &clock);
+            let _scd4x = scd4x.start_periodic_measurement()?;
+        }
+
+        assert!(simulator.is_measuring());
+        let result = simulator.write(DEFAULT_ADDRESS, &[0x23, 0x18]);
+        assert_eq!(
+            result,
+            Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_busy_sensor() -> Result<(), Error> {
+        let clock = VirtualClock::new();
+        let mut simulator = Simulator::new(&clock, rising_co2);
+
+        let mut scd4x = Scd4x::new(&mut simulator, &clock);
+
+        let pending = scd4x.begin_perform_self_test()?;
+        let result = scd4x.get_serial_number();
+        assert_eq!(
+            result,
+            Err(Error::I2c(ErrorKind::NoAcknowledge(
+                NoAcknowledgeSource::Address
+            )))
+        );
+
+        clock.advance(pending.duration());
+        assert!(scd4x.finish(pending)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_single_shot() -> Result<(), Error> {
+        let clock = VirtualClock::new();
+        let mut simulator = Simulator::new(&clock, rising_co2);
+
+        let scd4x = Scd4x::new(&mut simulator, &clock);
+        let mut scd4x = scd4x.measure_single_shot()?;
+        let sample = scd4x.read_measurement()?;
+        assert_eq!(rounded(&sample), (605.0, 21.0, 40.0));
+
+        let scd4x = scd4x.stop_periodic_measurement()?;
+        let mut scd4x = scd4x.measure_single_shot_rht_only()?;
+        let sample = scd4x.read_measurement()?;
+        assert_eq!(co2_to_word(sample.co2), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_persisted_settings() -> Result<(), Error> {
+        let clock = VirtualClock::new();
+        let mut simulator = Simulator::new(&clock, rising_co2);
+
+        {
+            let mut scd4x = Scd4x::new(&mut simulator, &clock);
+            scd4x.set_sensor_altitude(altitude_from_meter(1100.0))?;
+            scd4x.set_automatic_self_calibration_enabled(false)?;
+            scd4x.persist_settings()?;
+            scd4x.set_sensor_altitude(altitude_from_meter(1950.0))?;
+        }
+
+        simulator.power_cycle();
+        clock.advance(POWER_UP_TIME);
+
+        let mut scd4x = Scd4x::new(&mut simulator, &clock);
+        let altitude = scd4x.get_sensor_altitude()?;
+        assert_eq!(altitude, altitude_from_meter(1100.0));
+        assert!(!scd4x.get_automatic_self_calibration_enabled()?);
+
+        scd4x.perform_factory_reset()?;
+        let altitude = scd4x.get_sensor_altitude()?;
+        assert_eq!(altitude, altitude_from_meter(0.0));
+        assert!(scd4x.get_automatic_self_calibration_enabled()?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_temperature_offset() -> Result<(), Error> {
+        let clock = VirtualClock::new();
+        let mut simulator = Simulator::new(&clock, rising_co2);
+
+        let mut scd4x = Scd4x::new(&mut simulator, &clock);
+        scd4x.set_temperature_offset(temperature_from_celsius(6.0))?;
+        let mut scd4x = scd4x.measure_single_shot()?;
+        let sample = scd4x.read_measurement()?;
+
+        let celsius = celsius_from_temperature(sample.temperature);
+        let percent = number_from_humidity(sample.humidity);
+        assert!((celsius - 19.0).abs() < 0.01, "{celsius} != 19");
+        assert!(percent > 40.0, "{percent} <= 40");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_forced_recalibration() -> Result<(), Error> {
+        let clock = VirtualClock::new();
+        let mut simulator = Simulator::new(&clock, rising_co2);
+
+        let mut scd4x = Scd4x::new(&mut simulator, &clock);
+        let correction = scd4x.perform_forced_recalibration(co2_from_ppm(400.0))?;
+        assert_eq!(correction, None);
+
+        let mut scd4x = scd4x.measure_single_shot()?.stop_periodic_measurement()?;
+        let correction = scd4x.perform_forced_recalibration(co2_from_ppm(400.0))?;
+        assert_eq!(correction, Some(co2_from_ppm(-205.0)));
+
+        let mut scd4x = scd4x.measure_single_shot()?;
+        let sample = scd4x.read_measurement()?;
+        let ppm = ppm_from_co2(sample.co2);
+        assert!((ppm - 407.0).abs() < 0.001, "{ppm} != 407");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_wrong_address() {
+        let clock = VirtualClock::new();
+        let mut simulator = Simulator::new_with_address(&clock, 0x10, rising_co2);
+
+        let result = simulator.write(DEFAULT_ADDRESS, &[0x36, 0x82]);
+        assert_eq!(
+            result,
+            Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address))
+        );
+    }
+
+    #[test]
+    fn test_wrong_checksum() {
+        let clock = VirtualClock::new();
+        let mut simulator = Simulator::new(&clock, rising_co2);
+
+        let result = simulator.write(DEFAULT_ADDRESS, &[0x24, 0x27, 0x07, 0x9e, 0x00]);
+        assert_eq!(
+            result,
+            Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data))
+        );
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_async_sensor() -> Result<(), Error> {
+        let clock = VirtualClock::new();
+        let mut simulator = Simulator::new(&clock, rising_co2);
+
+        let scd4x = crate::AsyncScd4x::new(&mut simulator, &clock);
+        let mut scd4x = scd4x.start_periodic_measurement().await?;
+
+        clock.advance_to(Duration::from_secs(5));
+        let sample = scd4x.read_measurement().await?;
+        let ppm = ppm_from_co2(sample.co2);
+        assert!((ppm - 605.0).abs() < 0.001, "{ppm} != 605");
+
+        Ok(())
+    }
+}
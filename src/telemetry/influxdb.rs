@@ -0,0 +1,250 @@
+// Copyright Claudio Mattera 2024-2026.
+//
+// Distributed under the MIT License or the Apache 2.0 License at your option.
+// See the accompanying files LICENSE-MIT.txt and LICENSE-APACHE-2.0.txt, or
+// online at
+// https://opensource.org/licenses/MIT
+// https://opensource.org/licenses/Apache-2.0
+
+//! Formatter for InfluxDB line protocol
+
+use core::fmt::Display;
+use core::fmt::Error as FmtError;
+use core::fmt::Result as FmtResult;
+use core::fmt::Write;
+use core::time::Duration;
+
+use crate::sample::celsius_from_temperature;
+use crate::sample::number_from_humidity;
+use crate::sample::ppm_from_co2;
+use crate::Sample;
+
+use super::Escaper;
+use super::Tag;
+
+/// A formatter for InfluxDB line protocol
+///
+/// Each sample is written as a single line, with fields `co2_ppm`,
+/// `temperature_celsius` and `relative_humidity_percent` as floats.
+/// Measurement name, tag keys and tag values are escaped.
+/// Line protocol does not support newlines, so they are replaced with escaped
+/// spaces.
+/// Backslashes are escaped as well, so that a trailing backslash does not
+/// escape the following separator.
+/// Tags with an empty key or value are omitted, since line protocol does not
+/// allow them.
+///
+/// Line protocol does not support non-finite floats, so fields with
+/// non-finite values are omitted.
+#[derive(Copy, Clone)]
+pub struct InfluxDb<'tags> {
+    /// Name of the measurement
+    measurement: &'tags str,
+
+    /// Tags attached to each line
+    tags: &'tags [Tag<'tags>],
+}
+
+impl<'tags> InfluxDb<'tags> {
+    /// Create a new formatter from a measurement name and a list of tags
+    #[must_use]
+    pub const fn new(measurement: &'tags str, tags: &'tags [Tag<'tags>]) -> Self {
+        Self { measurement, tags }
+    }
+
+    /// Write a sample as a line, including the final newline
+    ///
+    /// The timestamp is expressed as time elapsed since the Unix epoch, and is
+    /// written with nanosecond precision.
+    /// Without a timestamp, the database uses the time of arrival.
+    ///
+    /// # Errors
+    ///
+    /// Return an error if the output cannot be written, if the measurement name
+    /// is empty, or if all values are non-finite.
+    pub fn write<W>(
+        &self,
+        output: &mut W,
+        sample: &Sample,
+        timestamp: Option<Duration>,
+    ) -> FmtResult
+    where
+        W: Write,
+    {
+        let fields = [
+            ("co2_ppm", ppm_from_co2(sample.co2)),
+            (
+                "temperature_celsius",
+                celsius_from_temperature(sample.temperature),
+            ),
+            (
+                "relative_humidity_percent",
+                number_from_humidity(sample.humidity),
+            ),
+        ];
+
+        if self.measurement.is_empty() || !fields.iter().any(|&(_, value)| value.is_finite()) {
+            return Err(FmtError);
+        }
+
+        Escaper::new(output, escape_measurement).write_str(self.measurement)?;
+
+        for tag in self.tags {
+            if tag.key.is_empty() || is_empty(tag.value) {
+                continue;
+            }
+
+            output.write_char(',')?;
+            Escaper::new(output, escape_key).write_str(tag.key)?;
+            output.write_char('=')?;
+            write!(Escaper::new(output, escape_key), "{}", tag.value)?;
+        }
+
+        let mut separator = ' ';
+        for (name, value) in fields {
+            if value.is_finite() {
+                write!(output, "{separator}{name}={value}")?;
+                separator = ',';
+            }
+        }
+
+        if let Some(timestamp) = timestamp {
+            write!(output, " {}", timestamp.as_nanos())?;
+        }
+
+        output.write_char('\n')
+    }
+}
+
+/// Return whether a value is formatted as an empty string
+fn is_empty(value: &dyn Display) -> bool {
+    /// A writer failing on the first non-empty string
+    struct Probe;
+
+    impl Write for Probe {
+        fn write_str(&mut self, string: &str) -> FmtResult {
+            if string.is_empty() {
+                Ok(())
+            } else {
+                Err(FmtError)
+            }
+        }
+    }
+
+    write!(Probe, "{value}").is_ok()
+}
+
+/// Escape a character in a measurement name
+fn escape_measurement(character: char) -> Option<&'static str> {
+    match character {
+        '\\' => Some("\\\\"),
+        ',' => Some("\\,"),
+        ' ' | '\n' | '\r' => Some("\\ "),
+        _ => None,
+    }
+}
+
+/// Escape a character in a tag key, tag value or field key
+fn escape_key(character: char) -> Option<&'static str> {
+    match character {
+        '\\' => Some("\\\\"),
+        ',' => Some("\\,"),
+        '=' => Some("\\="),
+        ' ' | '\n' | '\r' => Some("\\ "),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::sample::sample;
+    use crate::telemetry::SerialNumber;
+
+    /// Format a sample
+    fn format(influxdb: &InfluxDb, sample: &Sample, timestamp: Option<Duration>) -> String {
+        let mut output = String::new();
+        let result = influxdb.write(&mut output, sample, timestamp);
+        assert_eq!(result, Ok(()));
+        output
+    }
+
+    #[test]
+    fn test_without_tags() {
+        let influxdb = InfluxDb::new("scd4x", &[]);
+        let sample = sample(605.0, 21.5, 42.25);
+
+        assert_eq!(
+            format(&influxdb, &sample, None),
+            "scd4x co2_ppm=605,temperature_celsius=21.5,relative_humidity_percent=42.25\n"
+        );
+    }
+
+    #[test]
+    fn test_with_tags_and_timestamp() {
+        let serial_number = SerialNumber(0xf896_9f07_3bbe);
+        let tags = [Tag::new("serial", &serial_number), Tag::new("floor", &2_u8)];
+        let influxdb = InfluxDb::new("air", &tags);
+        let sample = sample(1250.0, -3.25, 97.5);
+        let timestamp = Duration::from_millis(1_767_225_605_001);
+
+        assert_eq!(
+            format(&influxdb, &sample, Some(timestamp)),
+            "air,serial=f8969f073bbe,floor=2 \
+             co2_ppm=1250,temperature_celsius=-3.25,relative_humidity_percent=97.5 \
+             1767225605001000000\n"
+        );
+    }
+
+    #[test]
+    fn test_escaping() {
+        let tags = [
+            Tag::new("room name", &"kitchen, north"),
+            Tag::new("a=b", &"c=d"),
+            Tag::new("floor\nlevel", &"first\nsecond\r\nthird"),
+            Tag::new("empty", &""),
+            Tag::new("", &"orphan"),
+            Tag::new("path\\", &"C:\\"),
+        ];
+        let influxdb = InfluxDb::new("my measurement,1=2\n\\", &tags);
+        let sample = sample(400.0, 20.0, 50.0);
+
+        let output = format(&influxdb, &sample, None);
+        assert_eq!(
+            output,
+            "my\\ measurement\\,1=2\\ \\\\,room\\ name=kitchen\\,\\ north,a\\=b=c\\=d,\
+             floor\\ level=first\\ second\\ \\ third,path\\\\=C:\\\\ \
+             co2_ppm=400,temperature_celsius=20,relative_humidity_percent=50\n"
+        );
+        assert_eq!(output.lines().count(), 1);
+    }
+
+    #[test]
+    fn test_empty_measurement() {
+        let influxdb = InfluxDb::new("", &[]);
+        let sample = sample(400.0, 20.0, 50.0);
+
+        let mut output = String::new();
+        let result = influxdb.write(&mut output, &sample, None);
+        assert_eq!(result, Err(FmtError));
+        assert_eq!(output, "");
+    }
+
+    #[test]
+    fn test_non_finite_values() {
+        let influxdb = InfluxDb::new("scd4x", &[]);
+
+        let partial = sample(f32::NAN, 21.5, f32::INFINITY);
+        assert_eq!(
+            format(&influxdb, &partial, None),
+            "scd4x temperature_celsius=21.5\n"
+        );
+
+        let invalid = sample(f32::NAN, f32::NAN, f32::NEG_INFINITY);
+        let mut output = String::new();
+        let result = influxdb.write(&mut output, &invalid, None);
+        assert_eq!(result, Err(FmtError));
+        assert_eq!(output, "");
+    }
+}
@@ -0,0 +1,150 @@
+// Copyright Claudio Mattera 2024-2026.
+//
+// Distributed under the MIT License or the Apache 2.0 License at your option.
+// See the accompanying files LICENSE-MIT.txt and LICENSE-APACHE-2.0.txt, or
+// online at
+// https://opensource.org/licenses/MIT
+// https://opensource.org/licenses/Apache-2.0
+
+//! Formatters for time-series databases and monitoring systems
+//!
+//! Samples can be written as [InfluxDB line protocol] with [`InfluxDb`], or
+//! as [Prometheus text exposition format] with [`Prometheus`].
+//! Both formatters write into any [`core::fmt::Write`] without allocating,
+//! and attach the same [`Tag`]s to every value, e.g. the serial number of the
+//! sensor.
+//!
+//! ~~~~
+//! use core::time::Duration;
+//!
+//! use scd4x_rs::telemetry::InfluxDb;
+//! use scd4x_rs::telemetry::SerialNumber;
+//! use scd4x_rs::telemetry::Tag;
+//! use scd4x_rs::Sample;
+//! # #[cfg(not(feature = "uom"))]
+//! # {
+//!
+//! let serial_number = SerialNumber(0xf896_9f07_3bbe);
+//! let tags = [
+//!     Tag::new("serial", &serial_number),
+//!     Tag::new("room", &"living room"),
+//! ];
+//! let influxdb = InfluxDb::new("scd4x", &tags);
+//!
+//! let sample = Sample { co2: 605.0, temperature: 21.5, humidity: 42.25 };
+//! let timestamp = Duration::from_secs(1_767_225_600);
+//!
+//! let mut line = String::new();
+//! influxdb.write(&mut line, &sample, Some(timestamp))?;
+//!
+//! assert_eq!(
+//!     line,
+//!     "scd4x,serial=f8969f073bbe,room=living\\ room \
+//!      co2_ppm=605,temperature_celsius=21.5,relative_humidity_percent=42.25 \
+//!      1767225600000000000\n",
+//! );
+//! # }
+//! # Ok::<(), core::fmt::Error>(())
+//! ~~~~
+//!
+//! [InfluxDB line protocol]: https://docs.influxdata.com/influxdb/v2/reference/syntax/line-protocol/
+//! [Prometheus text exposition format]: https://prometheus.io/docs/instrumenting/exposition_formats/
+
+mod influxdb;
+pub use self::influxdb::InfluxDb;
+
+mod prometheus;
+pub use self::prometheus::Metric;
+pub use self::prometheus::Prometheus;
+
+use core::fmt::Display;
+use core::fmt::Formatter;
+use core::fmt::Result as FmtResult;
+use core::fmt::Write;
+
+/// A key-value pair attached to every value
+///
+/// Tags are called labels in Prometheus.
+#[derive(Copy, Clone)]
+pub struct Tag<'tag> {
+    /// Key of the tag
+    key: &'tag str,
+
+    /// Value of the tag
+    value: &'tag dyn Display,
+}
+
+impl<'tag> Tag<'tag> {
+    /// Create a new tag
+    #[must_use]
+    pub const fn new(key: &'tag str, value: &'tag dyn Display) -> Self {
+        Self { key, value }
+    }
+}
+
+/// A serial number formatted as 12 hexadecimal digits
+///
+/// Serial numbers are returned by `get_serial_number()` as 48-bit integers.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SerialNumber(pub u64);
+
+impl Display for SerialNumber {
+    fn fmt(&self, formatter: &mut Formatter) -> FmtResult {
+        write!(formatter, "{:012x}", self.0)
+    }
+}
+
+/// A writer escaping characters before forwarding them to another writer
+struct Escaper<'output, W> {
+    /// Underlying writer
+    output: &'output mut W,
+
+    /// Function returning the escaped form of a character, if any
+    escape: fn(char) -> Option<&'static str>,
+}
+
+impl<'output, W> Escaper<'output, W>
+where
+    W: Write,
+{
+    /// Create a new escaper
+    fn new(output: &'output mut W, escape: fn(char) -> Option<&'static str>) -> Self {
+        Self { output, escape }
+    }
+}
+
+impl<W> Write for Escaper<'_, W>
+where
+    W: Write,
+{
+    fn write_str(&mut self, string: &str) -> FmtResult {
+        string
+            .chars()
+            .try_for_each(|character| self.write_char(character))
+    }
+
+    fn write_char(&mut self, character: char) -> FmtResult {
+        match (self.escape)(character) {
+            Some(escaped) => self.output.write_str(escaped),
+            None => self.output.write_char(character),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serial_number() {
+        let mut output = String::new();
+        let result = write!(output, "{}", SerialNumber(0xf896_9f07_3bbe));
+        assert_eq!(result, Ok(()));
+        assert_eq!(output, "f8969f073bbe");
+
+        output.clear();
+        let result = write!(output, "{}", SerialNumber(0x42));
+        assert_eq!(result, Ok(()));
+        assert_eq!(output, "000000000042");
+    }
+}
@@ -0,0 +1,429 @@
+// Copyright Claudio Mattera 2024-2026.
+//
+// Distributed under the MIT License or the Apache 2.0 License at your option.
+// See the accompanying files LICENSE-MIT.txt and LICENSE-APACHE-2.0.txt, or
+// online at
+// https://opensource.org/licenses/MIT
+// https://opensource.org/licenses/Apache-2.0
+
+//! Formatter for Prometheus text exposition format
+
+use core::fmt::Result as FmtResult;
+use core::fmt::Write;
+use core::time::Duration;
+
+use crate::sample::celsius_from_temperature;
+use crate::sample::number_from_humidity;
+use crate::sample::ppm_from_co2;
+use crate::Sample;
+
+use super::Escaper;
+use super::Tag;
+
+/// A gauge written for each sample
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Metric {
+    /// CO₂ concentration, `<name>_co2_ppm`
+    Co2,
+
+    /// Temperature, `<name>_temperature_celsius`
+    Temperature,
+
+    /// Relative humidity, `<name>_relative_humidity_ratio`
+    RelativeHumidity,
+}
+
+impl Metric {
+    /// All metrics, in the order they are written
+    pub const ALL: [Self; 3] = [Self::Co2, Self::Temperature, Self::RelativeHumidity];
+
+    /// Return the suffix of the metric name
+    const fn suffix(self) -> &'static str {
+        match self {
+            Self::Co2 => "co2_ppm",
+            Self::Temperature => "temperature_celsius",
+            Self::RelativeHumidity => "relative_humidity_ratio",
+        }
+    }
+
+    /// Return the description of the metric
+    const fn help(self) -> &'static str {
+        match self {
+            Self::Co2 => "CO₂ concentration in parts per million.",
+            Self::Temperature => "Temperature in degrees Celsius.",
+            Self::RelativeHumidity => "Relative humidity as a ratio between 0 and 1.",
+        }
+    }
+
+    /// Return the value of the metric in a sample
+    fn value(self, sample: &Sample) -> f32 {
+        match self {
+            Self::Co2 => ppm_from_co2(sample.co2),
+            Self::Temperature => celsius_from_temperature(sample.temperature),
+            Self::RelativeHumidity => number_from_humidity(sample.humidity) / 100.0,
+        }
+    }
+}
+
+/// A formatter for Prometheus text exposition format
+///
+/// Each sample is written as three gauges, `<name>_co2_ppm`,
+/// `<name>_temperature_celsius` and `<name>_relative_humidity_ratio`, each
+/// preceded by its `HELP` and `TYPE` lines.
+/// Following Prometheus conventions, relative humidity is expressed as a
+/// ratio between 0 and 1.
+///
+/// Characters that are not allowed in metric names and label names are
+/// replaced with underscores, names starting with a digit are prefixed with
+/// an underscore, and label values are escaped.
+/// Labels with an empty name are omitted.
+///
+/// Samples from several sensors must be grouped by metric, with a single
+/// `HELP` and `TYPE` header per metric.
+/// In that case, write each header once with [`Prometheus::write_header()`],
+/// followed by the samples of every sensor with
+/// [`Prometheus::write_sample()`].
+#[derive(Copy, Clone)]
+pub struct Prometheus<'tags> {
+    /// Prefix of the metric names
+    name: &'tags str,
+
+    /// Labels attached to each metric
+    labels: &'tags [Tag<'tags>],
+}
+
+impl<'tags> Prometheus<'tags> {
+    /// Create a new formatter from a prefix of metric names and a list of labels
+    #[must_use]
+    pub const fn new(name: &'tags str, labels: &'tags [Tag<'tags>]) -> Self {
+        Self { name, labels }
+    }
+
+    /// Write a sample as three gauges, each preceded by its header
+    ///
+    /// The timestamp is expressed as time elapsed since the Unix epoch, and is
+    /// written with millisecond precision.
+    /// Without a timestamp, the server uses the time of scraping.
+    ///
+    /// # Errors
+    ///
+    /// Return an error if the output cannot be written.
+    pub fn write<W>(
+        &self,
+        output: &mut W,
+        sample: &Sample,
+        timestamp: Option<Duration>,
+    ) -> FmtResult
+    where
+        W: Write,
+    {
+        for metric in Metric::ALL {
+            self.write_header(output, metric)?;
+            self.write_sample(output, metric, sample, timestamp)?;
+        }
+
+        Ok(())
+    }
+
+    /// Write the `HELP` and `TYPE` lines of a metric
+    ///
+    /// # Errors
+    ///
+    /// Return an error if the output cannot be written.
+    pub fn write_header<W>(&self, output: &mut W, metric: Metric) -> FmtResult
+    where
+        W: Write,
+    {
+        output.write_str("# HELP ")?;
+        self.write_name(output, metric)?;
+        writeln!(output, " {}", metric.help())?;
+
+        output.write_str("# TYPE ")?;
+        self.write_name(output, metric)?;
+        output.write_str(" gauge\n")
+    }
+
+    /// Write the value of a metric in a sample, without header
+    ///
+    /// The timestamp is written as in [`Prometheus::write()`].
+    ///
+    /// # Errors
+    ///
+    /// Return an error if the output cannot be written.
+    pub fn write_sample<W>(
+        &self,
+        output: &mut W,
+        metric: Metric,
+        sample: &Sample,
+        timestamp: Option<Duration>,
+    ) -> FmtResult
+    where
+        W: Write,
+    {
+        self.write_name(output, metric)?;
+
+        let mut separator = '{';
+        for label in self.labels {
+            if label.key.is_empty() {
+                continue;
+            }
+
+            output.write_char(separator)?;
+            write_identifier(output, label.key, escape_label_name)?;
+            output.write_str("=\"")?;
+            write!(Escaper::new(output, escape_label_value), "{}", label.value)?;
+            output.write_char('"')?;
+            separator = ',';
+        }
+        if separator == ',' {
+            output.write_char('}')?;
+        }
+
+        output.write_char(' ')?;
+        write_value(output, metric.value(sample))?;
+
+        if let Some(timestamp) = timestamp {
+            write!(output, " {}", timestamp.as_millis())?;
+        }
+
+        output.write_char('\n')
+    }
+
+    /// Write the name of a metric
+    fn write_name<W>(&self, output: &mut W, metric: Metric) -> FmtResult
+    where
+        W: Write,
+    {
+        if self.name.is_empty() {
+            output.write_str(metric.suffix())
+        } else {
+            write_identifier(output, self.name, escape_metric_name)?;
+            write!(output, "_{}", metric.suffix())
+        }
+    }
+}
+
+/// Write a metric or label name, prefixing it with an underscore if it starts
+/// with a digit
+fn write_identifier<W>(
+    output: &mut W,
+    identifier: &str,
+    escape: fn(char) -> Option<&'static str>,
+) -> FmtResult
+where
+    W: Write,
+{
+    if identifier.starts_with(|character: char| character.is_ascii_digit()) {
+        output.write_char('_')?;
+    }
+    Escaper::new(output, escape).write_str(identifier)
+}
+
+/// Write a value, including non-finite values
+fn write_value<W>(output: &mut W, value: f32) -> FmtResult
+where
+    W: Write,
+{
+    if value.is_nan() {
+        output.write_str("NaN")
+    } else if value == f32::INFINITY {
+        output.write_str("+Inf")
+    } else if value == f32::NEG_INFINITY {
+        output.write_str("-Inf")
+    } else {
+        write!(output, "{value}")
+    }
+}
+
+/// Replace a character not allowed in metric names
+fn escape_metric_name(character: char) -> Option<&'static str> {
+    match character {
+        'a'..='z' | 'A'..='Z' | '0'..='9' | '_' | ':' => None,
+        _ => Some("_"),
+    }
+}
+
+/// Replace a character not allowed in label names
+fn escape_label_name(character: char) -> Option<&'static str> {
+    match character {
+        'a'..='z' | 'A'..='Z' | '0'..='9' | '_' => None,
+        _ => Some("_"),
+    }
+}
+
+/// Escape a character in a label value
+fn escape_label_value(character: char) -> Option<&'static str> {
+    match character {
+        '\\' => Some("\\\\"),
+        '"' => Some("\\\""),
+        '\n' => Some("\\n"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::sample::sample;
+    use crate::telemetry::SerialNumber;
+
+    /// Format a sample
+    fn format(prometheus: &Prometheus, sample: &Sample, timestamp: Option<Duration>) -> String {
+        let mut output = String::new();
+        let result = prometheus.write(&mut output, sample, timestamp);
+        assert_eq!(result, Ok(()));
+        output
+    }
+
+    #[test]
+    fn test_without_labels() {
+        let prometheus = Prometheus::new("scd4x", &[]);
+        let sample = sample(605.0, 21.5, 42.5);
+
+        assert_eq!(
+            format(&prometheus, &sample, None),
+            "# HELP scd4x_co2_ppm CO₂ concentration in parts per million.\n\
+             # TYPE scd4x_co2_ppm gauge\n\
+             scd4x_co2_ppm 605\n\
+             # HELP scd4x_temperature_celsius Temperature in degrees Celsius.\n\
+             # TYPE scd4x_temperature_celsius gauge\n\
+             scd4x_temperature_celsius 21.5\n\
+             # HELP scd4x_relative_humidity_ratio Relative humidity as a ratio between 0 and 1.\n\
+             # TYPE scd4x_relative_humidity_ratio gauge\n\
+             scd4x_relative_humidity_ratio 0.425\n"
+        );
+    }
+
+    #[test]
+    fn test_with_labels_and_timestamp() {
+        let serial_number = SerialNumber(0xf896_9f07_3bbe);
+        let labels = [
+            Tag::new("serial", &serial_number),
+            Tag::new("room", &"office"),
+        ];
+        let prometheus = Prometheus::new("", &labels);
+        let sample = sample(1250.0, -3.25, 50.0);
+        let timestamp = Duration::from_millis(1_767_225_605_001);
+
+        assert_eq!(
+            format(&prometheus, &sample, Some(timestamp)),
+            "# HELP co2_ppm CO₂ concentration in parts per million.\n\
+             # TYPE co2_ppm gauge\n\
+             co2_ppm{serial=\"f8969f073bbe\",room=\"office\"} 1250 1767225605001\n\
+             # HELP temperature_celsius Temperature in degrees Celsius.\n\
+             # TYPE temperature_celsius gauge\n\
+             temperature_celsius{serial=\"f8969f073bbe\",room=\"office\"} -3.25 1767225605001\n\
+             # HELP relative_humidity_ratio Relative humidity as a ratio between 0 and 1.\n\
+             # TYPE relative_humidity_ratio gauge\n\
+             relative_humidity_ratio{serial=\"f8969f073bbe\",room=\"office\"} 0.5 1767225605001\n"
+        );
+    }
+
+    #[test]
+    fn test_several_sensors() {
+        let kitchen = [Tag::new("room", &"kitchen")];
+        let office = [Tag::new("room", &"office")];
+        let sensors = [
+            (
+                Prometheus::new("scd4x", &kitchen),
+                sample(605.0, 21.5, 42.5),
+            ),
+            (
+                Prometheus::new("scd4x", &office),
+                sample(1250.0, 19.0, 50.0),
+            ),
+        ];
+
+        let mut output = String::new();
+        for metric in Metric::ALL {
+            let result = sensors[0].0.write_header(&mut output, metric);
+            assert_eq!(result, Ok(()));
+            for &(prometheus, ref sample) in &sensors {
+                let result = prometheus.write_sample(&mut output, metric, sample, None);
+                assert_eq!(result, Ok(()));
+            }
+        }
+
+        assert_eq!(
+            output,
+            "# HELP scd4x_co2_ppm CO₂ concentration in parts per million.\n\
+             # TYPE scd4x_co2_ppm gauge\n\
+             scd4x_co2_ppm{room=\"kitchen\"} 605\n\
+             scd4x_co2_ppm{room=\"office\"} 1250\n\
+             # HELP scd4x_temperature_celsius Temperature in degrees Celsius.\n\
+             # TYPE scd4x_temperature_celsius gauge\n\
+             scd4x_temperature_celsius{room=\"kitchen\"} 21.5\n\
+             scd4x_temperature_celsius{room=\"office\"} 19\n\
+             # HELP scd4x_relative_humidity_ratio Relative humidity as a ratio between 0 and 1.\n\
+             # TYPE scd4x_relative_humidity_ratio gauge\n\
+             scd4x_relative_humidity_ratio{room=\"kitchen\"} 0.425\n\
+             scd4x_relative_humidity_ratio{room=\"office\"} 0.5\n"
+        );
+    }
+
+    #[test]
+    fn test_escaping() {
+        let labels = [
+            Tag::new("room name", &"main \"lab\"\\\nnorth"),
+            Tag::new("", &"orphan"),
+            Tag::new("2nd", &"floor"),
+        ];
+        let prometheus = Prometheus::new("air-quality.sensor", &labels);
+        let sample = sample(400.0, 20.0, 50.0);
+
+        let output = format(&prometheus, &sample, None);
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(
+            lines.get(..3),
+            Some(
+                [
+                    "# HELP air_quality_sensor_co2_ppm CO₂ concentration in parts per million.",
+                    "# TYPE air_quality_sensor_co2_ppm gauge",
+                    "air_quality_sensor_co2_ppm{room_name=\"main \\\"lab\\\"\\\\\\nnorth\",_2nd=\"floor\"} 400",
+                ]
+                .as_slice()
+            )
+        );
+
+        let labels = [Tag::new("", &"orphan")];
+        let prometheus = Prometheus::new("4x", &labels);
+
+        let output = format(&prometheus, &sample, None);
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(
+            lines.get(..3),
+            Some(
+                [
+                    "# HELP _4x_co2_ppm CO₂ concentration in parts per million.",
+                    "# TYPE _4x_co2_ppm gauge",
+                    "_4x_co2_ppm 400",
+                ]
+                .as_slice()
+            )
+        );
+    }
+
+    #[test]
+    fn test_non_finite_values() {
+        let prometheus = Prometheus::new("scd4x", &[]);
+        let sample = sample(f32::NAN, f32::INFINITY, f32::NEG_INFINITY);
+
+        let output = format(&prometheus, &sample, None);
+        let values: Vec<&str> = output
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .collect();
+
+        assert_eq!(
+            values,
+            [
+                "scd4x_co2_ppm NaN",
+                "scd4x_temperature_celsius +Inf",
+                "scd4x_relative_humidity_ratio -Inf",
+            ]
+        );
+    }
+}
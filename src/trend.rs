@@ -0,0 +1,262 @@
+// Copyright Claudio Mattera 2024-2026.
+//
+// Distributed under the MIT License or the Apache 2.0 License at your option.
+// See the accompanying files LICENSE-MIT.txt and LICENSE-APACHE-2.0.txt, or
+// online at
+// https://opensource.org/licenses/MIT
+// https://opensource.org/licenses/Apache-2.0
+
+//! Data types and functions for detecting trends in CO₂ concentration
+//!
+//! A [`Co2Trend`] keeps the last `N` timestamped CO₂ concentrations, and it
+//! estimates their rate of change with a linear regression.
+//! Timestamps are durations since an arbitrary, fixed origin, e.g. the boot of
+//! the device.
+//!
+//! ~~~~
+//! use core::time::Duration;
+//!
+//! use scd4x_rs::trend::Co2Trend;
+//! use scd4x_rs::trend::Trend;
+//! use scd4x_rs::Sample;
+//! # #[cfg(not(feature = "uom"))]
+//! # {
+//!
+//! let mut trend = Co2Trend::<12>::new(5.0);
+//!
+//! for minute in 0..10 {
+//!     let timestamp = Duration::from_secs(60 * minute);
+//!     let co2 = 600.0 + 30.0 * minute as f32;
+//!     trend.update(timestamp, Sample { co2, temperature: 21.0, humidity: 40.0 });
+//! }
+//!
+//! assert_eq!(trend.trend(), Some(Trend::Rising));
+//! # }
+//! ~~~~
+
+use core::time::Duration;
+
+use crate::sample::ppm_from_co2;
+use crate::util::linear_regression;
+use crate::Sample;
+
+/// Direction of change of CO₂ concentration
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Trend {
+    /// CO₂ concentration is rising
+    Rising,
+
+    /// CO₂ concentration is stable
+    Stable,
+
+    /// CO₂ concentration is falling
+    Falling,
+}
+
+/// A tracker of the rate of change of CO₂ concentration
+///
+/// The rate of change is the slope of the linear regression over the last `N`
+/// samples, expressed in ppm per minute.
+/// The concentration is stable when the absolute value of the rate is below a
+/// threshold.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Co2Trend<const N: usize> {
+    /// Threshold between stable and rising or falling, in ppm per minute
+    threshold: f32,
+
+    /// Last timestamps and CO₂ concentrations in ppm, in a circular buffer
+    points: [(Duration, f32); N],
+
+    /// Number of points in the buffer
+    length: usize,
+
+    /// Position of the next point in the buffer
+    next: usize,
+}
+
+impl<const N: usize> Co2Trend<N> {
+    /// Create a new tracker with a threshold in ppm per minute
+    #[must_use]
+    pub fn new(threshold: f32) -> Self {
+        Self {
+            threshold,
+            points: [(Duration::ZERO, 0.0); N],
+            length: 0,
+            next: 0,
+        }
+    }
+
+    /// Return the threshold between stable and rising or falling, in ppm per
+    /// minute
+    #[must_use]
+    pub fn threshold(&self) -> f32 {
+        self.threshold
+    }
+
+    /// Return the number of samples kept by the tracker
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Return whether the tracker does not keep any sample
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Add a timestamped sample to the tracker
+    ///
+    /// If the tracker is full, the oldest sample is discarded.
+    pub fn update(&mut self, timestamp: Duration, sample: Sample) {
+        if N > 0 {
+            self.points[self.next] = (timestamp, ppm_from_co2(sample.co2));
+            self.next = (self.next + 1) % N;
+            self.length = (self.length + 1).min(N);
+        }
+    }
+
+    /// Return the rate of change of CO₂ concentration in ppm per minute
+    ///
+    /// Return `None` if fewer than two samples were added, or if all samples
+    /// have the same timestamp.
+    #[must_use]
+    pub fn rate(&self) -> Option<f32> {
+        let points = &self.points[..self.length];
+
+        let origin = points.iter().map(|&(timestamp, _)| timestamp).min()?;
+
+        let mut minutes_and_ppm = [(0.0, 0.0); N];
+        for (point, &(timestamp, ppm)) in minutes_and_ppm.iter_mut().zip(points) {
+            let minutes = timestamp.saturating_sub(origin).as_secs_f32() / 60.0;
+            *point = (minutes, ppm);
+        }
+
+        let fit = linear_regression(&minutes_and_ppm[..self.length])?;
+        Some(fit.slope)
+    }
+
+    /// Return the direction of change of CO₂ concentration
+    ///
+    /// Return `None` if the rate of change is not available.
+    #[must_use]
+    pub fn trend(&self) -> Option<Trend> {
+        let rate = self.rate()?;
+
+        let trend = if rate >= self.threshold {
+            Trend::Rising
+        } else if rate <= -self.threshold {
+            Trend::Falling
+        } else {
+            Trend::Stable
+        };
+
+        Some(trend)
+    }
+
+    /// Forget all samples
+    pub fn reset(&mut self) {
+        self.length = 0;
+        self.next = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::sample::co2_from_ppm;
+    use crate::sample::humidity_from_number;
+    use crate::sample::temperature_from_celsius;
+
+    /// Create a sample with the given CO₂ concentration in ppm
+    fn sample(ppm: f32) -> Sample {
+        Sample {
+            co2: co2_from_ppm(ppm),
+            temperature: temperature_from_celsius(21.0),
+            humidity: humidity_from_number(40.0),
+        }
+    }
+
+    /// Feed a ramp sampled every 5 seconds to a tracker
+    fn feed_ramp<const N: usize>(
+        trend: &mut Co2Trend<N>,
+        start: Duration,
+        count: u32,
+        initial: f32,
+        rate: f32,
+    ) {
+        for index in 0..count {
+            let elapsed = Duration::from_secs(5) * index;
+            let ppm = initial + rate * elapsed.as_secs_f32() / 60.0;
+            trend.update(start + elapsed, sample(ppm));
+        }
+    }
+
+    /// Assert that the rate is close to the expected one
+    fn assert_rate<const N: usize>(trend: &Co2Trend<N>, expected: f32) {
+        let actual = trend.rate().unwrap_or(f32::NAN);
+        assert!((actual - expected).abs() < 0.01, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn test_rising_ramp() {
+        let mut trend = Co2Trend::<24>::new(5.0);
+        feed_ramp(&mut trend, Duration::from_secs(3600), 30, 500.0, 25.0);
+
+        assert_eq!(trend.len(), 24);
+        assert_rate(&trend, 25.0);
+        assert_eq!(trend.trend(), Some(Trend::Rising));
+    }
+
+    #[test]
+    fn test_falling_ramp() {
+        let mut trend = Co2Trend::<24>::new(5.0);
+        feed_ramp(&mut trend, Duration::ZERO, 24, 1500.0, -12.0);
+
+        assert_rate(&trend, -12.0);
+        assert_eq!(trend.trend(), Some(Trend::Falling));
+    }
+
+    #[test]
+    fn test_stable_with_noise() {
+        let mut trend = Co2Trend::<24>::new(5.0);
+
+        for index in 0..24_u32 {
+            let timestamp = Duration::from_secs(5) * index;
+            let noise = if index % 2 == 0 { 30.0 } else { -30.0 };
+            trend.update(timestamp, sample(800.0 + noise));
+        }
+
+        let rate = trend.rate().unwrap_or(f32::NAN);
+        assert!(rate.abs() < 5.0, "{rate}");
+        assert_eq!(trend.trend(), Some(Trend::Stable));
+    }
+
+    #[test]
+    fn test_window_rolls_over() {
+        let mut trend = Co2Trend::<12>::new(5.0);
+
+        feed_ramp(&mut trend, Duration::ZERO, 12, 500.0, 30.0);
+        assert_eq!(trend.trend(), Some(Trend::Rising));
+
+        feed_ramp(&mut trend, Duration::from_secs(60), 12, 530.0, -20.0);
+        assert_rate(&trend, -20.0);
+        assert_eq!(trend.trend(), Some(Trend::Falling));
+    }
+
+    #[test]
+    fn test_not_enough_samples() {
+        let mut trend = Co2Trend::<12>::new(5.0);
+        assert_eq!(trend.rate(), None);
+
+        trend.update(Duration::from_secs(10), sample(600.0));
+        assert_eq!(trend.rate(), None);
+
+        trend.update(Duration::from_secs(10), sample(700.0));
+        assert_eq!(trend.trend(), None);
+
+        trend.reset();
+        assert!(trend.is_empty());
+    }
+}
@@ -9,14 +9,14 @@
 //! Data types and functions for miscellaneous utilities
 
 use crate::verify_checksum;
-use crate::Error;
+use crate::ProtocolError;
 
 /// Convert a 9-bytes buffer to three words
 ///
 /// # Errors
 ///
 /// Returns an error if the checksum does not match.
-pub(crate) fn buffer_to_three_words(buffer: [u8; 9]) -> Result<(u16, u16, u16), Error> {
+pub(crate) fn buffer_to_three_words(buffer: [u8; 9]) -> Result<(u16, u16, u16), ProtocolError> {
     let word0 = buffer_to_word(buffer[0], buffer[1], buffer[2])?;
     let word1 = buffer_to_word(buffer[3], buffer[4], buffer[5])?;
     let word2 = buffer_to_word(buffer[6], buffer[7], buffer[8])?;
@@ -24,15 +24,86 @@
     Ok((word0, word1, word2))
 }
 
+/// Convert a 9-bytes buffer to three words, validating each word independently
+///
+/// A word is `None` if its checksum does not match.
+pub(crate) fn buffer_to_three_words_lenient(
+    buffer: [u8; 9],
+) -> (Option<u16>, Option<u16>, Option<u16>) {
+    let word0 = buffer_to_word(buffer[0], buffer[1], buffer[2]).ok();
+    let word1 = buffer_to_word(buffer[3], buffer[4], buffer[5]).ok();
+    let word2 = buffer_to_word(buffer[6], buffer[7], buffer[8]).ok();
+
+    (word0, word1, word2)
+}
+
 /// Convert three bytes buffer to a word
 ///
 /// # Errors
 ///
 /// Returns an error if the checksum does not match.
-pub(crate) fn buffer_to_word(byte0: u8, byte1: u8, byte2: u8) -> Result<u16, Error> {
+pub(crate) fn buffer_to_word(byte0: u8, byte1: u8, byte2: u8) -> Result<u16, ProtocolError> {
     let word = (u16::from(byte0) << 8_i32) + u16::from(byte1);
 
-    verify_checksum([byte0, byte1], byte2)?;
+    verify_checksum(&[byte0, byte1], byte2)?;
 
     Ok(word)
 }
+
+/// Result of a linear regression
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) struct LinearFit {
+    /// Slope of the fitted line
+    pub(crate) slope: f32,
+
+    /// Intercept of the fitted line
+    pub(crate) intercept: f32,
+
+    /// Coefficient of determination
+    pub(crate) r_squared: f32,
+}
+
+/// Fit a line to a set of points with ordinary least squares
+///
+/// Return `None` if the points have no variance along the x axis.
+pub(crate) fn linear_regression(points: &[(f32, f32)]) -> Option<LinearFit> {
+    #[expect(
+        clippy::cast_precision_loss,
+        reason = "Number of points is small enough to be represented exactly"
+    )]
+    let count = points.len() as f32;
+
+    let mean_x = points.iter().map(|&(x, _)| x).sum::<f32>() / count;
+    let mean_y = points.iter().map(|&(_, y)| y).sum::<f32>() / count;
+
+    let (covariance, variance_x, variance_y) = points.iter().fold(
+        (0.0, 0.0, 0.0),
+        |(covariance, variance_x, variance_y), &(x, y)| {
+            let dx = x - mean_x;
+            let dy = y - mean_y;
+            (
+                covariance + dx * dy,
+                variance_x + dx * dx,
+                variance_y + dy * dy,
+            )
+        },
+    );
+
+    if variance_x <= 0.0 {
+        return None;
+    }
+
+    let slope = covariance / variance_x;
+    let intercept = mean_y - slope * mean_x;
+    let r_squared = if variance_y > 0.0 {
+        covariance * covariance / (variance_x * variance_y)
+    } else {
+        1.0
+    };
+
+    Some(LinearFit {
+        slope,
+        intercept,
+        r_squared,
+    })
+}
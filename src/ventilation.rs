@@ -0,0 +1,266 @@
+// Copyright Claudio Mattera 2024-2026.
+//
+// Distributed under the MIT License or the Apache 2.0 License at your option.
+// See the accompanying files LICENSE-MIT.txt and LICENSE-APACHE-2.0.txt, or
+// online at
+// https://opensource.org/licenses/MIT
+// https://opensource.org/licenses/Apache-2.0
+
+//! Data types and functions for estimating ventilation rates
+//!
+//! Once a room is no longer occupied, its CO₂ concentration decays
+//! exponentially towards the outdoor concentration:
+//!
+//! C(t) = C<sub>out</sub> + (C<sub>0</sub> − C<sub>out</sub>) e<sup>−λ t</sup>
+//!
+//! where λ is the ventilation rate in air changes per hour (ACH).
+//! A [`VentilationEstimator`] fits this curve to the samples collected during
+//! the decay, with a linear regression of ln(C − C<sub>out</sub>) over time.
+//!
+//! ~~~~
+//! use core::time::Duration;
+//!
+//! use scd4x_rs::ventilation::VentilationEstimator;
+//! use scd4x_rs::Sample;
+//! # #[cfg(not(feature = "uom"))]
+//! # {
+//!
+//! let mut estimator = VentilationEstimator::<32>::new(420.0);
+//!
+//! for minute in 0..30 {
+//!     let hours = minute as f32 / 60.0;
+//!     let co2 = 420.0 + 1000.0 * (-1.5 * hours).exp();
+//!     let timestamp = Duration::from_secs(60 * minute);
+//!     estimator.update(timestamp, Sample { co2, temperature: 21.0, humidity: 40.0 });
+//! }
+//!
+//! let estimate = estimator.estimate().unwrap();
+//! assert!((estimate.air_changes_per_hour - 1.5).abs() < 0.01);
+//! # }
+//! ~~~~
+
+use core::time::Duration;
+
+use libm::logf;
+
+use crate::sample::ppm_from_co2;
+use crate::util::linear_regression;
+use crate::Co2;
+use crate::Sample;
+
+/// An estimate of the ventilation rate
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Estimate {
+    /// Ventilation rate in air changes per hour
+    pub air_changes_per_hour: f32,
+
+    /// Quality of the fit, as coefficient of determination between 0 and 1
+    ///
+    /// Values close to 1 indicate that the samples follow an exponential decay
+    /// closely.
+    pub r_squared: f32,
+}
+
+/// An estimator of the ventilation rate from the decay of CO₂ concentration
+///
+/// The estimator keeps the last `N` timestamped samples.
+/// It should be reset when occupancy drops, so that it only contains samples
+/// from the decay.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VentilationEstimator<const N: usize> {
+    /// Outdoor CO₂ concentration in ppm
+    outdoor: f32,
+
+    /// Last timestamps and CO₂ concentrations in ppm, in a circular buffer
+    points: [(Duration, f32); N],
+
+    /// Number of points in the buffer
+    length: usize,
+
+    /// Position of the next point in the buffer
+    next: usize,
+}
+
+impl<const N: usize> VentilationEstimator<N> {
+    /// Create a new estimator for the given outdoor CO₂ concentration
+    #[must_use]
+    pub fn new(outdoor: Co2) -> Self {
+        Self {
+            outdoor: ppm_from_co2(outdoor),
+            points: [(Duration::ZERO, 0.0); N],
+            length: 0,
+            next: 0,
+        }
+    }
+
+    /// Return the number of samples kept by the estimator
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Return whether the estimator does not keep any sample
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Add a timestamped sample to the estimator
+    ///
+    /// Samples at or below the outdoor CO₂ concentration carry no information
+    /// about the decay, and they are ignored.
+    /// If the estimator is full, the oldest sample is discarded.
+    pub fn update(&mut self, timestamp: Duration, sample: Sample) {
+        let ppm = ppm_from_co2(sample.co2);
+
+        if N > 0 && ppm > self.outdoor {
+            self.points[self.next] = (timestamp, ppm);
+            self.next = (self.next + 1) % N;
+            self.length = (self.length + 1).min(N);
+        }
+    }
+
+    /// Estimate the ventilation rate
+    ///
+    /// Return `None` if fewer than two samples were added, if all samples have
+    /// the same timestamp, or if the CO₂ concentration is not decaying.
+    #[must_use]
+    pub fn estimate(&self) -> Option<Estimate> {
+        let points = &self.points[..self.length];
+
+        let origin = points.iter().map(|&(timestamp, _)| timestamp).min()?;
+
+        let mut hours_and_logarithms = [(0.0, 0.0); N];
+        for (point, &(timestamp, ppm)) in hours_and_logarithms.iter_mut().zip(points) {
+            let hours = timestamp.saturating_sub(origin).as_secs_f32() / 3600.0;
+            *point = (hours, logf(ppm - self.outdoor));
+        }
+
+        let fit = linear_regression(&hours_and_logarithms[..self.length])?;
+
+        (fit.slope < 0.0).then_some(Estimate {
+            air_changes_per_hour: -fit.slope,
+            r_squared: fit.r_squared,
+        })
+    }
+
+    /// Forget all samples
+    pub fn reset(&mut self) {
+        self.length = 0;
+        self.next = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use libm::expf;
+
+    use crate::sample::co2_from_ppm;
+    use crate::sample::humidity_from_number;
+    use crate::sample::temperature_from_celsius;
+
+    /// Create a sample with the given CO₂ concentration in ppm
+    fn sample(ppm: f32) -> Sample {
+        Sample {
+            co2: co2_from_ppm(ppm),
+            temperature: temperature_from_celsius(21.0),
+            humidity: humidity_from_number(40.0),
+        }
+    }
+
+    /// Feed a decay curve sampled every minute to an estimator
+    fn feed_decay<const N: usize>(
+        estimator: &mut VentilationEstimator<N>,
+        outdoor: f32,
+        initial: f32,
+        air_changes_per_hour: f32,
+        noise: f32,
+    ) {
+        for minute in 0..60_u16 {
+            let hours = f32::from(minute) / 60.0;
+            let decay = (initial - outdoor) * expf(-air_changes_per_hour * hours);
+            let noise = if minute % 2 == 0 { noise } else { -noise };
+            let timestamp = Duration::from_secs(60 * u64::from(minute));
+            estimator.update(timestamp, sample(outdoor + decay + noise));
+        }
+    }
+
+    #[test]
+    fn test_exact_decay() {
+        let mut estimator = VentilationEstimator::<64>::new(co2_from_ppm(420.0));
+        feed_decay(&mut estimator, 420.0, 1600.0, 2.0, 0.0);
+
+        let estimate = estimator.estimate();
+        let air_changes_per_hour =
+            estimate.map_or(f32::NAN, |estimate| estimate.air_changes_per_hour);
+        let r_squared = estimate.map_or(f32::NAN, |estimate| estimate.r_squared);
+
+        assert!(
+            (air_changes_per_hour - 2.0).abs() < 0.01,
+            "{air_changes_per_hour}"
+        );
+        assert!(r_squared > 0.999, "{r_squared}");
+    }
+
+    #[test]
+    fn test_noisy_decay() {
+        let mut estimator = VentilationEstimator::<64>::new(co2_from_ppm(420.0));
+        feed_decay(&mut estimator, 420.0, 1600.0, 0.8, 15.0);
+
+        let estimate = estimator.estimate();
+        let air_changes_per_hour =
+            estimate.map_or(f32::NAN, |estimate| estimate.air_changes_per_hour);
+        let r_squared = estimate.map_or(f32::NAN, |estimate| estimate.r_squared);
+
+        assert!(
+            (air_changes_per_hour - 0.8).abs() < 0.08,
+            "{air_changes_per_hour}"
+        );
+        assert!(r_squared > 0.9, "{r_squared}");
+        assert!(r_squared < 0.999, "{r_squared}");
+    }
+
+    #[test]
+    fn test_rolling_window() {
+        let mut estimator = VentilationEstimator::<20>::new(co2_from_ppm(400.0));
+        feed_decay(&mut estimator, 400.0, 2000.0, 3.0, 0.0);
+
+        assert_eq!(estimator.len(), 20);
+
+        let estimate = estimator.estimate();
+        let air_changes_per_hour =
+            estimate.map_or(f32::NAN, |estimate| estimate.air_changes_per_hour);
+
+        assert!(
+            (air_changes_per_hour - 3.0).abs() < 0.01,
+            "{air_changes_per_hour}"
+        );
+    }
+
+    #[test]
+    fn test_samples_below_outdoor_are_ignored() {
+        let mut estimator = VentilationEstimator::<8>::new(co2_from_ppm(420.0));
+
+        estimator.update(Duration::from_secs(0), sample(410.0));
+        estimator.update(Duration::from_secs(60), sample(420.0));
+
+        assert!(estimator.is_empty());
+        assert_eq!(estimator.estimate(), None);
+    }
+
+    #[test]
+    fn test_rising_concentration() {
+        let mut estimator = VentilationEstimator::<8>::new(co2_from_ppm(420.0));
+
+        estimator.update(Duration::from_secs(0), sample(600.0));
+        estimator.update(Duration::from_secs(60), sample(700.0));
+        estimator.update(Duration::from_secs(120), sample(800.0));
+
+        assert_eq!(estimator.estimate(), None);
+
+        estimator.reset();
+        assert!(estimator.is_empty());
+    }
+}